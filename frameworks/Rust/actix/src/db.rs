@@ -7,12 +7,10 @@ use futures::{
     stream::futures_unordered::FuturesUnordered, FutureExt, StreamExt, TryStreamExt,
 };
 use rand::{rngs::SmallRng, Rng, SeedableRng};
+use tfb_common::{fortune, Fortune, World};
 use tokio_postgres::{connect, types::ToSql, Client, NoTls, Statement};
 
-use crate::{
-    models::World,
-    utils::{Fortune, Writer},
-};
+use crate::utils::Writer;
 
 #[derive(Debug)]
 pub enum PgError {
@@ -136,7 +134,7 @@ impl PgConnection {
 
             worlds.push(self.query_one_world(w_id).map(move |res| match res {
                 Ok(mut world) => {
-                    world.randomnumber = id;
+                    world.random_number = id;
                     Ok(world)
                 }
 
@@ -152,7 +150,7 @@ impl PgConnection {
 
         for w in &worlds {
             params.push(&w.id);
-            params.push(&w.randomnumber);
+            params.push(&w.random_number);
         }
 
         for w in &worlds {
@@ -165,10 +163,7 @@ impl PgConnection {
     }
 
     pub async fn tell_fortune(&self) -> Result<Vec<Fortune>, PgError> {
-        let mut items = vec![Fortune {
-            id: 0,
-            message: Cow::Borrowed("Additional fortune added at request time."),
-        }];
+        let mut items = Vec::with_capacity(13);
 
         let fut = self.client.query_raw::<_, _, &[i32; 0]>(&self.fortune, &[]);
 
@@ -184,7 +179,7 @@ impl PgConnection {
             });
         }
 
-        items.sort_by(|it, next| it.message.cmp(&next.message));
+        fortune::prepare(&mut items);
         Ok(items)
     }
 }
@@ -5,8 +5,7 @@ use std::io;
 use actix::prelude::*;
 use diesel::prelude::*;
 use rand::{rngs::SmallRng, Rng, SeedableRng};
-
-use crate::models;
+use tfb_common::{Fortune, World};
 
 pub struct DbExecutor {
     conn: PgConnection,
@@ -14,6 +13,16 @@ pub struct DbExecutor {
 }
 
 impl DbExecutor {
+    fn load_world(&mut self, world_id: i32) -> QueryResult<World> {
+        use crate::schema::world::dsl::*;
+
+        world
+            .filter(id.eq(world_id))
+            .select((id, randomnumber))
+            .first::<(i32, i32)>(&mut self.conn)
+            .map(|(w_id, number)| World::new(w_id, number))
+    }
+
     pub fn new(db_url: &str) -> DbExecutor {
         DbExecutor {
             conn: PgConnection::establish(db_url)
@@ -30,47 +39,35 @@ impl Actor for DbExecutor {
 pub struct RandomWorld;
 
 impl Message for RandomWorld {
-    type Result = io::Result<models::World>;
+    type Result = io::Result<World>;
 }
 
 impl Handler<RandomWorld> for DbExecutor {
-    type Result = io::Result<models::World>;
+    type Result = io::Result<World>;
 
     fn handle(&mut self, _: RandomWorld, _: &mut Self::Context) -> Self::Result {
-        use crate::schema::world::dsl::*;
-
         let random_id = self.rng.random_range(1..10_001);
-        match world
-            .filter(id.eq(random_id))
-            .load::<models::World>(&mut self.conn)
-        {
-            Ok(mut items) => Ok(items.pop().unwrap()),
-            Err(_) => Err(io::Error::new(io::ErrorKind::Other, "Database error")),
-        }
+        self.load_world(random_id)
+            .map_err(|_| io::Error::other("Database error"))
     }
 }
 
 pub struct RandomWorlds(pub u16);
 
 impl Message for RandomWorlds {
-    type Result = io::Result<Vec<models::World>>;
+    type Result = io::Result<Vec<World>>;
 }
 
 impl Handler<RandomWorlds> for DbExecutor {
-    type Result = io::Result<Vec<models::World>>;
+    type Result = io::Result<Vec<World>>;
 
     fn handle(&mut self, msg: RandomWorlds, _: &mut Self::Context) -> Self::Result {
-        use crate::schema::world::dsl::*;
-
         let mut worlds = Vec::with_capacity(msg.0 as usize);
         for _ in 0..msg.0 {
             let w_id = self.rng.random_range(1..10_001);
-            let w = match world.filter(id.eq(w_id)).load::<models::World>(&mut self.conn) {
-                Ok(mut items) => items.pop().unwrap(),
-                Err(_) => {
-                    return Err(io::Error::new(io::ErrorKind::Other, "Database error"));
-                }
-            };
+            let w = self
+                .load_world(w_id)
+                .map_err(|_| io::Error::other("Database error"))?;
             worlds.push(w)
         }
         Ok(worlds)
@@ -80,11 +77,11 @@ impl Handler<RandomWorlds> for DbExecutor {
 pub struct UpdateWorld(pub u16);
 
 impl Message for UpdateWorld {
-    type Result = io::Result<Vec<models::World>>;
+    type Result = io::Result<Vec<World>>;
 }
 
 impl Handler<UpdateWorld> for DbExecutor {
-    type Result = io::Result<Vec<models::World>>;
+    type Result = io::Result<Vec<World>>;
 
     fn handle(&mut self, msg: UpdateWorld, _: &mut Self::Context) -> Self::Result {
         use crate::schema::world::dsl::*;
@@ -92,13 +89,10 @@ impl Handler<UpdateWorld> for DbExecutor {
         let mut worlds = Vec::with_capacity(msg.0 as usize);
         for _ in 0..msg.0 {
             let w_id: i32 = self.rng.random_range(1..10_001);
-            let mut w = match world.filter(id.eq(w_id)).load::<models::World>(&mut self.conn) {
-                Ok(mut items) => items.pop().unwrap(),
-                Err(_) => {
-                    return Err(io::Error::new(io::ErrorKind::Other, "Database error"));
-                }
-            };
-            w.randomnumber = self.rng.random_range(1..10_001);
+            let mut w = self
+                .load_world(w_id)
+                .map_err(|_| io::Error::other("Database error"))?;
+            w.random_number = self.rng.random_range(1..10_001);
             worlds.push(w);
         }
         worlds.sort_by_key(|w| w.id);
@@ -107,12 +101,12 @@ impl Handler<UpdateWorld> for DbExecutor {
             for w in &worlds {
                 diesel::update(world)
                     .filter(id.eq(w.id))
-                    .set(randomnumber.eq(w.randomnumber))
+                    .set(randomnumber.eq(w.random_number))
                     .execute(conn)?;
             }
             Ok(())
         })
-        .map_err(|e: diesel::result::Error| io::Error::new(io::ErrorKind::Other, e))?;
+        .map_err(|e: diesel::result::Error| io::Error::other(e))?;
 
         Ok(worlds)
     }
@@ -121,25 +115,25 @@ impl Handler<UpdateWorld> for DbExecutor {
 pub struct TellFortune;
 
 impl Message for TellFortune {
-    type Result = io::Result<Vec<models::Fortune>>;
+    type Result = io::Result<Vec<Fortune>>;
 }
 
 impl Handler<TellFortune> for DbExecutor {
-    type Result = io::Result<Vec<models::Fortune>>;
+    type Result = io::Result<Vec<Fortune>>;
 
     fn handle(&mut self, _: TellFortune, _: &mut Self::Context) -> Self::Result {
         use crate::schema::fortune::dsl::*;
 
-        match fortune.load::<models::Fortune>(&mut self.conn) {
-            Ok(mut items) => {
-                items.push(models::Fortune {
-                    id: 0,
-                    message: "Additional fortune added at request time.".to_string(),
-                });
-                items.sort_by(|it, next| it.message.cmp(&next.message));
+        match fortune.load::<(i32, String)>(&mut self.conn) {
+            Ok(rows) => {
+                let mut items: Vec<Fortune> = rows
+                    .into_iter()
+                    .map(|(f_id, f_message)| Fortune::new(f_id, f_message))
+                    .collect();
+                tfb_common::fortune::prepare(&mut items);
                 Ok(items)
             }
-            Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
+            Err(e) => Err(io::Error::other(e)),
         }
     }
 }
@@ -15,7 +15,6 @@ use futures::future::{ok, LocalBoxFuture};
 use yarte::ywrite_html;
 
 mod db;
-mod models;
 mod utils;
 
 use crate::{
@@ -148,12 +148,14 @@ async fn fortune(data: web::Data<Data>) -> Result<HttpResponse<Vec<u8>>> {
             .await?;
 
         let mut fortunes: Vec<Fortune> = fortunes_cursor.try_collect().await?;
-        fortunes.push(Fortune {
-            id: 0,
-            message: "Additional fortune added at request time.".to_string(),
-        });
-
-        fortunes.sort_by(|a, b| a.message.cmp(&b.message));
+        tfb_common::fortune::prepare_with(
+            &mut fortunes,
+            |id, message| Fortune {
+                id,
+                message: message.to_string(),
+            },
+            |fortune| &fortune.message,
+        );
 
         Ok(fortunes)
     }
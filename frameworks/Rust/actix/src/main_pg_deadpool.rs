@@ -1,4 +1,3 @@
-mod models;
 mod utils;
 
 use std::fmt::Write;
@@ -14,8 +13,8 @@ use actix_web::{
 };
 use deadpool_postgres::{Config, Pool, PoolConfig, Runtime};
 use futures::{stream::FuturesUnordered, TryStreamExt};
-use models::{Fortune, World};
 use rand::{prelude::SmallRng, Rng, SeedableRng};
+use tfb_common::{Fortune, World};
 use tokio_postgres::{types::ToSql, NoTls};
 use utils::{Queries, Result, CONNECTION_POOL_SIZE};
 use yarte::ywrite_html;
@@ -32,10 +31,7 @@ async fn find_random_world(pool: &Pool) -> Result<World> {
 
     let row = conn.query_one(&world, &[&id]).await?;
 
-    Ok(World {
-        id: row.get(0),
-        randomnumber: row.get(1),
-    })
+    Ok(World::new(row.get(0), row.get(1)))
 }
 
 async fn find_random_worlds(pool: &Pool, num_of_worlds: usize) -> Result<Vec<World>> {
@@ -98,20 +94,20 @@ async fn updates(
     let mut rng = SmallRng::from_os_rng();
 
     let mut updates = "UPDATE world SET randomnumber = CASE id ".to_string();
-    let mut params: Vec<&(dyn ToSql + Sync)> = Vec::with_capacity(query.q as usize * 3);
+    let mut params: Vec<&(dyn ToSql + Sync)> = Vec::with_capacity(query.q * 3);
 
     let mut n_params = 1;
     for world in worlds.iter_mut() {
         let new_random_number = (rng.random::<u32>() % 10_000 + 1) as i32;
         write!(&mut updates, "when ${} then ${} ", n_params, n_params + 1).unwrap();
-        world.randomnumber = new_random_number;
+        world.random_number = new_random_number;
         n_params += 2;
     }
 
     // need separate loop to borrow immutably
     for world in worlds.iter() {
         params.push(&world.id);
-        params.push(&world.randomnumber);
+        params.push(&world.random_number);
     }
 
     updates.push_str("ELSE randomnumber END WHERE id IN (");
@@ -151,18 +147,10 @@ async fn fortune(data: web::Data<Pool>) -> Result<HttpResponse<Vec<u8>>> {
     let mut fortunes = Vec::new();
 
     while let Some(row) = stream.try_next().await? {
-        fortunes.push(Fortune {
-            id: row.get(0),
-            message: row.get(1),
-        });
+        fortunes.push(Fortune::new(row.get(0), row.get::<_, String>(1)));
     }
 
-    fortunes.push(Fortune {
-        id: 0,
-        message: "Additional fortune added at request time.".to_string(),
-    });
-
-    fortunes.sort_by(|a, b| a.message.cmp(&b.message));
+    tfb_common::fortune::prepare(&mut fortunes);
 
     let mut body = Vec::with_capacity(2048);
     ywrite_html!(body, "{{> fortune }}");
@@ -7,7 +7,7 @@ use std::pin::Pin;
 use std::task::{Context, Poll};
 
 use actix_codec::AsyncWrite;
-use actix_http::{h1, Request};
+use actix_http::{h1, Request, Version};
 use tokio_util::codec::Decoder;
 use actix_rt::net::TcpStream;
 use actix_server::Server;
@@ -16,7 +16,6 @@ use bytes::{Buf, BufMut, BytesMut};
 use simd_json_derive::Serialize;
 use tokio::io::{AsyncBufRead, BufReader};
 
-mod models;
 mod utils;
 
 use crate::utils::Writer;
@@ -25,10 +24,19 @@ const HEAD_JSON: &[u8] =
     b"HTTP/1.1 200 OK\r\nServer: A\r\nContent-Type: application/json\r\nContent-Length: 27\r\n";
 const HEAD_PLAIN: &[u8] =
     b"HTTP/1.1 200 OK\r\nServer: A\r\nContent-Type: text/plain\r\nContent-Length: 13\r\n";
-const HEAD_NOT_FOUND: &[u8] = b"HTTP/1.1 204 OK\r\nServer: A\r\n";
+const HEAD_NOT_FOUND: &[u8] =
+    b"HTTP/1.1 404 Not Found\r\nServer: A\r\nContent-Length: 0\r\n";
+const HEAD_BAD_REQUEST: &[u8] =
+    b"HTTP/1.1 400 Bad Request\r\nServer: A\r\nContent-Length: 0\r\n";
+const CONNECTION_CLOSE: &[u8] = b"Connection: close\r\n";
+const CONNECTION_KEEP_ALIVE: &[u8] = b"Connection: keep-alive\r\n";
 const BODY_PLAIN: &[u8] = b"Hello, World!";
 const HDR_END: &[u8] = b"\r\n";
 
+/// Once this many response bytes are waiting for the socket, no more requests are
+/// decoded or read until the peer catches up.
+const WRITE_HIGH_WATER: usize = 65_536;
+
 #[derive(Serialize)]
 pub struct Message {
     pub message: &'static str,
@@ -39,39 +47,111 @@ struct App {
     read_buf: BytesMut,
     write_buf: BytesMut,
     codec: h1::Codec,
+    /// Set when the last response was written: the peer asked to close or sent
+    /// a malformed request.
+    closing: bool,
+    /// Set when the peer shut down its side of the connection. The requests
+    /// already buffered are still answered.
+    eof: bool,
 }
 
 impl App {
+    fn new(io: TcpStream) -> Self {
+        App {
+            io: BufReader::new(io),
+            read_buf: BytesMut::with_capacity(32_768),
+            write_buf: BytesMut::with_capacity(32_768),
+            codec: h1::Codec::default(),
+            closing: false,
+            eof: false,
+        }
+    }
+
     fn handle_request(&mut self, req: Request) {
+        // The codec derives this from the request version and `Connection` header.
+        let keep_alive = self.codec.keep_alive();
+        let version = req.version();
+
         match req.path() {
             "/json" => {
                 let message = Message {
                     message: "Hello, World!",
                 };
-                self.write_buf.put_slice(HEAD_JSON);
-                self.codec
-                    .config()
-                    .write_date_header(&mut self.write_buf, false);
-                self.write_buf.put_slice(HDR_END);
+                self.write_head(HEAD_JSON, version, keep_alive);
                 message
                     .json_write(&mut Writer(&mut self.write_buf))
                     .unwrap();
             }
 
             "/plaintext" => {
-                self.write_buf.put_slice(HEAD_PLAIN);
-                self.codec
-                    .config()
-                    .write_date_header(&mut self.write_buf, false);
-                self.write_buf.put_slice(HDR_END);
+                self.write_head(HEAD_PLAIN, version, keep_alive);
                 self.write_buf.put_slice(BODY_PLAIN);
             }
 
-            _ => {
-                self.write_buf.put_slice(HEAD_NOT_FOUND);
-                self.write_buf.put_slice(HDR_END);
+            _ => self.write_head(HEAD_NOT_FOUND, version, keep_alive),
+        }
+
+        if !keep_alive {
+            self.closing = true;
+        }
+    }
+
+    /// Answer a request the codec could not parse. The rest of the input cannot be
+    /// framed, so the connection is closed once the response is written.
+    fn handle_error(&mut self) {
+        self.write_head(HEAD_BAD_REQUEST, Version::HTTP_11, false);
+        self.closing = true;
+    }
+
+    fn write_head(&mut self, head: &[u8], version: Version, keep_alive: bool) {
+        self.write_buf.put_slice(head);
+        self.codec
+            .config()
+            .write_date_header(&mut self.write_buf, false);
+        if !keep_alive {
+            self.write_buf.put_slice(CONNECTION_CLOSE);
+        } else if version == Version::HTTP_10 {
+            self.write_buf.put_slice(CONNECTION_KEEP_ALIVE);
+        }
+        self.write_buf.put_slice(HDR_END);
+    }
+
+    /// Answer the buffered requests, in order, until the response buffer is full.
+    /// Returns whether every complete request in the read buffer was answered.
+    fn decode_requests(&mut self) -> bool {
+        while !self.closing && self.write_buf.len() < WRITE_HIGH_WATER {
+            match self.codec.decode(&mut self.read_buf) {
+                Ok(Some(h1::Message::Item(req))) => self.handle_request(req),
+                // No route reads the request body.
+                Ok(Some(h1::Message::Chunk(_))) => {}
+                Ok(None) => return true,
+                Err(_) => self.handle_error(),
             }
         }
+        false
+    }
+
+    /// Write as much of the buffered responses as the socket accepts, returning
+    /// whether anything was written. If data is left over, the socket will wake the
+    /// task once it is writable again.
+    fn flush(&mut self, cx: &mut Context<'_>) -> io::Result<bool> {
+        let len = self.write_buf.len();
+        let mut written = 0;
+        while written < len {
+            match Pin::new(&mut self.io).poll_write(cx, &self.write_buf[written..]) {
+                Poll::Pending => break,
+                Poll::Ready(Ok(0)) => return Err(io::ErrorKind::WriteZero.into()),
+                Poll::Ready(Ok(n)) => written += n,
+                Poll::Ready(Err(err)) => return Err(err),
+            }
+        }
+
+        if written == len {
+            self.write_buf.clear();
+        } else if written > 0 {
+            self.write_buf.advance(written);
+        }
+        Ok(written > 0)
     }
 }
 
@@ -82,64 +162,45 @@ impl Future for App {
         let this = self.get_mut();
 
         loop {
-            if this.read_buf.capacity() - this.read_buf.len() < 512 {
-                this.read_buf.reserve(32_768);
-            }
-
-            let n = match Pin::new(&mut this.io).poll_fill_buf(cx) {
-                Poll::Pending => break,
-                Poll::Ready(Ok(filled)) => {
-                    if filled.is_empty() {
-                        return Poll::Ready(Ok(()));
-                    }
+            let mut progress = false;
 
-                    this.read_buf.extend_from_slice(filled);
-                    filled.len()
+            // Stop reading while the peer is not consuming the responses, so that a
+            // client pipelining requests cannot grow the buffers without bound.
+            if !this.closing && !this.eof && this.write_buf.len() < WRITE_HIGH_WATER {
+                if this.read_buf.capacity() - this.read_buf.len() < 512 {
+                    this.read_buf.reserve(32_768);
                 }
-                Poll::Ready(Err(_)) => return Poll::Ready(Err(())),
-            };
 
-            Pin::new(&mut this.io).consume(n);
-        }
+                match Pin::new(&mut this.io).poll_fill_buf(cx) {
+                    Poll::Pending => {}
+                    // The peer is done sending; answer what it already sent.
+                    Poll::Ready(Ok([])) => this.eof = true,
+                    Poll::Ready(Ok(filled)) => {
+                        let n = filled.len();
+                        this.read_buf.extend_from_slice(filled);
+                        Pin::new(&mut this.io).consume(n);
+                        progress = true;
+                    }
+                    Poll::Ready(Err(_)) => return Poll::Ready(Err(())),
+                }
+            }
 
-        if this.write_buf.capacity() - this.write_buf.len() <= 512 {
-            this.write_buf.reserve(32_768);
-        }
+            let drained = this.decode_requests();
 
-        loop {
-            match this.codec.decode(&mut this.read_buf) {
-                Ok(Some(h1::Message::Item(req))) => this.handle_request(req),
-                Ok(None) => break,
-                _ => return Poll::Ready(Err(())),
+            match this.flush(cx) {
+                Ok(written) => progress |= written,
+                Err(_) => return Poll::Ready(Err(())),
             }
-        }
 
-        if !this.write_buf.is_empty() {
-            let len = this.write_buf.len();
-            let mut written = 0;
-            while written < len {
-                match Pin::new(&mut this.io).poll_write(cx, &this.write_buf[written..]) {
-                    Poll::Pending => {
-                        break;
-                    }
-                    Poll::Ready(Ok(n)) => {
-                        if n == 0 {
-                            return Poll::Ready(Ok(()));
-                        } else {
-                            written += n;
-                        }
-                    }
-                    Poll::Ready(Err(_)) => return Poll::Ready(Err(())),
-                }
+            if (this.closing || this.eof && drained) && this.write_buf.is_empty() {
+                return Poll::Ready(Ok(()));
             }
 
-            if written == len {
-                unsafe { this.write_buf.set_len(0) }
-            } else if written > 0 {
-                this.write_buf.advance(written);
+            // Every I/O call that made no progress registered the waker.
+            if !progress {
+                return Poll::Pending;
             }
         }
-        Poll::Pending
     }
 }
 
@@ -151,13 +212,191 @@ async fn main() -> io::Result<()> {
     Server::build()
         .backlog(1024)
         .bind("tfb-actix-server", "0.0.0.0:8080", || {
-            fn_service(|io: TcpStream| App {
-                io: BufReader::new(io),
-                read_buf: BytesMut::with_capacity(32_768),
-                write_buf: BytesMut::with_capacity(32_768),
-                codec: h1::Codec::default(),
-            })
+            fn_service(|io: TcpStream| App::new(io))
         })?
         .run()
         .await
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::Shutdown;
+    use std::sync::mpsc;
+    use std::thread;
+
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// Send `input` and half-close, serve the connection with `App` and return
+    /// everything the client received.
+    async fn exchange(input: &'static [u8]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (sent_tx, sent_rx) = mpsc::channel();
+
+        let client = thread::spawn(move || {
+            let mut stream = std::net::TcpStream::connect(addr).unwrap();
+            stream.write_all(input).unwrap();
+            stream.shutdown(Shutdown::Write).unwrap();
+            sent_tx.send(()).unwrap();
+
+            let mut output = Vec::new();
+            stream.read_to_end(&mut output).unwrap();
+            output
+        });
+
+        let (io, _) = listener.accept().await.unwrap();
+        sent_rx.recv().unwrap();
+        App::new(io).await.unwrap();
+
+        String::from_utf8(client.join().unwrap()).unwrap()
+    }
+
+    /// The status lines of `output`, in order.
+    fn statuses(output: &str) -> Vec<&str> {
+        output
+            .match_indices("HTTP/1.1 ")
+            .map(|(start, _)| {
+                let line = &output[start..];
+                &line[..line.find("\r\n").unwrap()]
+            })
+            .collect()
+    }
+
+    /// The head of the `index`-th response in `output`.
+    fn head(output: &str, index: usize) -> &str {
+        let start = output.match_indices("HTTP/1.1 ").nth(index).unwrap().0;
+        let len = output[start..].find("\r\n\r\n").unwrap();
+        &output[start..start + len]
+    }
+
+    /// Requests pipelined ahead of a half-close are all answered, including the
+    /// ones held back while the responses were above `WRITE_HIGH_WATER`.
+    #[actix_rt::test]
+    async fn answers_pipelined_requests_after_half_close() {
+        const REQUESTS: usize = 1_000;
+        const REQUEST: &[u8] = b"GET /plaintext HTTP/1.1\r\nHost: a\r\n\r\n";
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (sent_tx, sent_rx) = mpsc::channel();
+
+        let client = thread::spawn(move || {
+            let mut stream = std::net::TcpStream::connect(addr).unwrap();
+            stream.write_all(&REQUEST.repeat(REQUESTS)).unwrap();
+            stream.shutdown(Shutdown::Write).unwrap();
+            sent_tx.send(()).unwrap();
+
+            let mut responses = Vec::new();
+            stream.read_to_end(&mut responses).unwrap();
+            responses
+        });
+
+        let (io, _) = listener.accept().await.unwrap();
+        sent_rx.recv().unwrap();
+
+        // Read every request and the end of input in two reads, so that the
+        // end of input arrives while requests are still waiting to be decoded.
+        let mut app = App::new(io);
+        app.io = BufReader::with_capacity(REQUESTS * REQUEST.len(), app.io.into_inner());
+        app.await.unwrap();
+
+        let responses = client.join().unwrap();
+        let answered = responses
+            .windows(BODY_PLAIN.len())
+            .filter(|window| *window == BODY_PLAIN)
+            .count();
+        assert_eq!(answered, REQUESTS);
+    }
+
+    #[actix_rt::test]
+    async fn unknown_path_is_not_found_with_empty_body() {
+        let output =
+            exchange(b"GET /missing HTTP/1.1\r\n\r\nGET /plaintext HTTP/1.1\r\n\r\n").await;
+
+        assert_eq!(
+            statuses(&output),
+            ["HTTP/1.1 404 Not Found", "HTTP/1.1 200 OK"]
+        );
+        let not_found = head(&output, 0);
+        assert!(not_found.contains("\r\nContent-Length: 0"), "{not_found}");
+        assert!(!not_found.contains("Connection: close"), "{not_found}");
+        assert!(output.ends_with(std::str::from_utf8(BODY_PLAIN).unwrap()));
+    }
+
+    #[actix_rt::test]
+    async fn malformed_request_is_answered_then_closed() {
+        let output = exchange(b"NOT HTTP\r\n\r\nGET /plaintext HTTP/1.1\r\n\r\n").await;
+
+        assert_eq!(statuses(&output), ["HTTP/1.1 400 Bad Request"]);
+        let bad_request = head(&output, 0);
+        assert!(
+            bad_request.contains("\r\nContent-Length: 0"),
+            "{bad_request}"
+        );
+        assert!(
+            bad_request.contains("\r\nConnection: close"),
+            "{bad_request}"
+        );
+        assert!(output.ends_with("\r\n\r\n"));
+    }
+
+    #[actix_rt::test]
+    async fn connection_close_ends_the_connection() {
+        let output = exchange(
+            b"GET /plaintext HTTP/1.1\r\nConnection: close\r\n\r\nGET /json HTTP/1.1\r\n\r\n",
+        )
+        .await;
+
+        assert_eq!(statuses(&output), ["HTTP/1.1 200 OK"]);
+        assert!(head(&output, 0).contains("\r\nConnection: close"));
+        assert!(output.ends_with(std::str::from_utf8(BODY_PLAIN).unwrap()));
+    }
+
+    #[actix_rt::test]
+    async fn http_10_closes_unless_kept_alive() {
+        let output =
+            exchange(b"GET /plaintext HTTP/1.0\r\n\r\nGET /json HTTP/1.0\r\n\r\n").await;
+        assert_eq!(statuses(&output), ["HTTP/1.1 200 OK"]);
+        assert!(head(&output, 0).contains("\r\nConnection: close"));
+
+        let output = exchange(
+            b"GET /plaintext HTTP/1.0\r\nConnection: keep-alive\r\n\r\nGET /json HTTP/1.0\r\n\r\n",
+        )
+        .await;
+        assert_eq!(statuses(&output), ["HTTP/1.1 200 OK", "HTTP/1.1 200 OK"]);
+        assert!(head(&output, 0).contains("\r\nConnection: keep-alive"));
+        assert!(head(&output, 1).contains("\r\nConnection: close"));
+    }
+
+    /// Decoding stops once `WRITE_HIGH_WATER` response bytes are buffered, and
+    /// resumes after they are written.
+    #[actix_rt::test]
+    async fn decoding_pauses_above_write_high_water() {
+        const REQUEST: &[u8] = b"GET /plaintext HTTP/1.1\r\n\r\n";
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let _client = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (io, _) = listener.accept().await.unwrap();
+
+        let mut app = App::new(io);
+        let requests = 2 * WRITE_HIGH_WATER / REQUEST.len();
+        app.read_buf.extend_from_slice(&REQUEST.repeat(requests));
+
+        assert!(!app.decode_requests());
+        let buffered = app.write_buf.len();
+        assert!(buffered >= WRITE_HIGH_WATER);
+        assert!(!app.read_buf.is_empty());
+
+        // Decoding again without writing answers nothing more.
+        assert!(!app.decode_requests());
+        assert_eq!(app.write_buf.len(), buffered);
+
+        app.write_buf.clear();
+        while !app.decode_requests() {
+            app.write_buf.clear();
+        }
+        assert!(app.read_buf.is_empty());
+    }
+}
@@ -12,9 +12,9 @@ use actix_web::{
 };
 use askama::Template;
 use bytes::BytesMut;
+use tfb_common::Fortune;
 
 mod db_diesel;
-mod models;
 mod schema;
 mod utils;
 
@@ -91,7 +91,7 @@ async fn updates(
 #[derive(Template)]
 #[template(path = "fortune.html")]
 struct FortuneTemplate<'a> {
-    items: &'a Vec<models::Fortune>,
+    items: &'a Vec<Fortune>,
 }
 
 async fn fortune(db: web::Data<Addr<db_diesel::DbExecutor>>) -> Result<HttpResponse, Error> {
@@ -1,23 +1,12 @@
 #![allow(dead_code, unused_braces)]
 
-use std::{borrow::Cow, cmp, fmt::Display, io};
+use std::{fmt::Display, io};
 
 use bytes::BufMut;
-use serde::{Deserialize, Serialize};
-
-#[derive(Serialize, Debug)]
-pub struct Fortune {
-    pub id: i32,
-    pub message: Cow<'static, str>,
-}
+use serde::Deserialize;
 
 pub const JSON_MSG_SIZE: usize = 27;
 
-#[derive(Serialize, Deserialize)]
-pub struct Message {
-    pub message: &'static str,
-}
-
 /// An `io::Write`r that only requires mutable reference and assumes that there is space available
 /// in the buffer for every write operation or that it can be extended implicitly (like
 /// `bytes::BytesMut`, for example).
@@ -37,12 +26,7 @@ impl<'a, B: BufMut> io::Write for Writer<'a, B> {
 }
 
 pub fn get_query_param(query: &str) -> u16 {
-    let q = if let Some(pos) = query.find('q') {
-        query.split_at(pos + 2).1.parse::<u16>().ok().unwrap_or(1)
-    } else {
-        1
-    };
-    cmp::min(500, cmp::max(1, q))
+    tfb_common::query::queries(query) as u16
 }
 
 pub const CONNECTION_POOL_SIZE: usize = 40;
@@ -80,19 +64,7 @@ impl<'de> Deserialize<'de> for Queries {
     where
         D: serde::Deserializer<'de>,
     {
-        #[derive(Debug, Deserialize)]
-        struct Q {
-            q: Option<String>,
-        }
-
-        let q = Q::deserialize(deserializer)?;
-        let n = match q.q {
-            Some(s) => {
-                let i: i32 = s.parse().unwrap_or(1);
-                std::cmp::max(1, std::cmp::min(500, i)) as usize
-            }
-            None => 1,
-        };
-        Ok(Queries { q: n })
+        let params = tfb_common::query::Params::deserialize(deserializer)?;
+        Ok(Queries { q: params.count() })
     }
 }
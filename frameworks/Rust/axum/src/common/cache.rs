@@ -0,0 +1,86 @@
+//! An in-memory copy of the `World` table for the cached queries test.
+//!
+//! The cache knows nothing about the database it was loaded from: every backend
+//! provides a function returning all worlds, and [`preload`] stores them once for
+//! the whole process, so that the runtimes started by `server::start_tokio` share
+//! a single copy.
+
+use std::fmt::Debug;
+
+use axum::{extract::Query, http::StatusCode, response::IntoResponse};
+use rand::{rng, rngs::SmallRng, SeedableRng};
+use tfb_common::{random_ids, World};
+use tokio::sync::OnceCell;
+
+#[cfg(not(feature = "simd-json"))]
+use axum::Json;
+
+#[cfg(feature = "simd-json")]
+use crate::common::simd_json::Json;
+use crate::common::utils::{parse_params, Params};
+
+static CACHE: OnceCell<WorldCache> = OnceCell::const_new();
+
+/// All worlds, sorted by id.
+pub struct WorldCache {
+    worlds: Box<[World]>,
+}
+
+impl WorldCache {
+    pub fn new(worlds: impl IntoIterator<Item = World>) -> Self {
+        let mut worlds: Box<[World]> = worlds.into_iter().collect();
+        worlds.sort_unstable_by_key(|world| world.id);
+        Self { worlds }
+    }
+
+    #[inline]
+    pub fn get(&self, id: i32) -> Option<World> {
+        self.worlds
+            .binary_search_by_key(&id, |world| world.id)
+            .ok()
+            .map(|index| self.worlds[index])
+    }
+}
+
+/// Fill the process-wide cache with the worlds returned by `load`.
+///
+/// Only the first caller runs `load`; the others wait for it to finish and get the
+/// same cache. Panics if the worlds cannot be loaded, as the server cannot answer
+/// the cached queries test without them.
+pub async fn preload<F, E>(load: impl FnOnce() -> F) -> &'static WorldCache
+where
+    F: Future<Output = Result<Vec<World>, E>>,
+    E: Debug,
+{
+    CACHE
+        .get_or_init(|| async {
+            let worlds = load().await.expect("error loading cached worlds");
+            WorldCache::new(worlds)
+        })
+        .await
+}
+
+/// Handler for the cached queries test. [`preload`] must have been awaited before
+/// the server starts.
+///
+/// An id missing from the cache fails the request rather than shortening the
+/// response.
+pub async fn cached_queries(
+    Query(params): Query<Params>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let cache = CACHE.get().expect("world cache was not preloaded");
+    let count = parse_params(params);
+    let mut rng = SmallRng::from_rng(&mut rng());
+
+    let worlds = random_ids(&mut rng, count)
+        .map(|id| cache.get(id).ok_or(id))
+        .collect::<Result<Vec<World>, i32>>()
+        .map_err(|id| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("world {id} is not in the cache"),
+            )
+        })?;
+
+    Ok((StatusCode::OK, Json(worlds)))
+}
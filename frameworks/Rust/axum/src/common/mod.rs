@@ -1,8 +1,7 @@
 use std::{env, str::FromStr};
 
 use core::fmt::Debug;
-use rand::{distr::Uniform, rngs::SmallRng, Rng, RngCore};
-pub mod models;
+pub mod cache;
 pub mod utils;
 
 #[cfg(feature = "simd-json")]
@@ -16,10 +15,6 @@ pub const SELECT_WORLD_BY_ID: &str =
 #[allow(dead_code)]
 pub const SELECT_ALL_CACHED_WORLDS: &str =
     "SELECT id, randomnumber FROM world ORDER BY id";
-#[allow(dead_code)]
-pub const UPDATE_WORLDS: &str = r#"UPDATE world SET randomnumber = new.rnum FROM
-    (SELECT * FROM UNNEST($1::int[], $2::int[]) AS v(id, rnum) ORDER BY 1) AS new
-WHERE world.id = new.id"#;
 
 /// Return the value of an environment variable.
 #[allow(dead_code)]
@@ -32,18 +27,3 @@ where
         .parse::<T>()
         .unwrap_or_else(|_| panic!("could not parse {key}"))
 }
-
-/// Generate a single integer in the range 1 to 10,000 (inclusive)
-#[allow(dead_code)]
-#[inline(always)]
-pub fn random_id(rng: &mut impl RngCore) -> i32 {
-    rng.random_range(1..=10_000)
-}
-
-/// Generate an iterator of integers in the range 1 to 10,000 (inclusive)
-#[allow(dead_code)]
-#[inline(always)]
-pub fn random_ids(rng: &mut SmallRng, count: usize) -> impl Iterator<Item = i32> + use<'_> {
-    rng.sample_iter(Uniform::new_inclusive(1, 10_000).unwrap())
-        .take(count)
-}
\ No newline at end of file
@@ -1,7 +1,7 @@
 use axum::extract::rejection::JsonRejection::MissingJsonContentType;
 use axum::extract::Request;
 use axum::extract::{rejection::*, FromRequest};
-use axum::{async_trait, http};
+use axum::http;
 use axum_core::response::{IntoResponse, Response};
 use bytes::{BufMut, Bytes, BytesMut};
 use http::{
@@ -14,15 +14,47 @@ use simd_json;
 #[derive(Debug, Clone, Copy, Default)]
 pub struct Json<T>(pub T);
 
+/// Rejection used for [`Json`], mirroring axum's `JsonRejection`.
+#[derive(Debug)]
 pub enum SimdJsonRejection {
+    /// The `Content-Type` is not JSON.
     Json(JsonRejection),
+    /// The body could not be buffered.
     Bytes(BytesRejection),
-    Simd(String),
+    /// The body is not valid JSON.
+    Syntax(simd_json::Error),
+    /// The body is valid JSON but does not match the target type. The error carries the path
+    /// of the field that failed.
+    Data(serde_path_to_error::Error<simd_json::Error>),
+}
+
+impl SimdJsonRejection {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            SimdJsonRejection::Json(rejection) => rejection.status(),
+            SimdJsonRejection::Bytes(rejection) => rejection.status(),
+            SimdJsonRejection::Syntax(_) => StatusCode::BAD_REQUEST,
+            SimdJsonRejection::Data(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    pub fn body_text(&self) -> String {
+        match self {
+            SimdJsonRejection::Json(rejection) => rejection.body_text(),
+            SimdJsonRejection::Bytes(rejection) => rejection.body_text(),
+            SimdJsonRejection::Syntax(err) => {
+                format!("Failed to parse the request body as JSON: {err}")
+            }
+            SimdJsonRejection::Data(err) => {
+                format!("Failed to deserialize the JSON body into the target type: {err}")
+            }
+        }
+    }
 }
 
 impl IntoResponse for SimdJsonRejection {
     fn into_response(self) -> Response {
-        todo!()
+        (self.status(), self.body_text()).into_response()
     }
 }
 
@@ -40,11 +72,16 @@ impl From<BytesRejection> for SimdJsonRejection {
 
 impl From<simd_json::Error> for SimdJsonRejection {
     fn from(err: simd_json::Error) -> Self {
-        SimdJsonRejection::Simd(err.to_string())
+        SimdJsonRejection::Syntax(err)
+    }
+}
+
+impl From<serde_path_to_error::Error<simd_json::Error>> for SimdJsonRejection {
+    fn from(err: serde_path_to_error::Error<simd_json::Error>) -> Self {
+        SimdJsonRejection::Data(err)
     }
 }
 
-#[async_trait]
 impl<T, S> FromRequest<S> for Json<T>
 where
     T: DeserializeOwned,
@@ -83,11 +120,8 @@ fn json_content_type(headers: &HeaderMap) -> bool {
         return false;
     };
 
-    let is_json_content_type = mime.type_() == "application"
-        && (mime.subtype() == "json"
-            || mime.suffix().map_or(false, |name| name == "json"));
-
-    is_json_content_type
+    mime.type_() == "application"
+        && (mime.subtype() == "json" || mime.suffix().is_some_and(|name| name == "json"))
 }
 
 axum_core::__impl_deref!(Json);
@@ -107,15 +141,10 @@ where
     /// constructing a `Json<T>`.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, SimdJsonRejection> {
         let body = &mut bytes.to_owned();
-        let deserializer = simd_json::from_slice::<T>(body);
-
-        let value = match deserializer {
-            Ok(v) => v,
-            Err(err) => {
-                let rejection = { SimdJsonRejection::from(err) };
-                return Err(rejection);
-            }
-        };
+        // Building the tape validates the whole document, so any error past this point is a
+        // mismatch between the JSON and `T`.
+        let mut deserializer = simd_json::Deserializer::from_slice(body)?;
+        let value = serde_path_to_error::deserialize(&mut deserializer)?;
 
         Ok(Json(value))
     }
@@ -150,3 +179,90 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Payload {
+        inner: Inner,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Inner {
+        id: i32,
+    }
+
+    fn json_request(body: impl Into<Body>) -> Request {
+        Request::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body.into())
+            .unwrap()
+    }
+
+    #[test]
+    fn valid_body() {
+        let Json(payload) = Json::<Payload>::from_bytes(br#"{"inner":{"id":1}}"#).unwrap();
+        assert_eq!(payload.inner.id, 1);
+    }
+
+    #[test]
+    fn syntax_error_is_bad_request() {
+        let rejection = Json::<Payload>::from_bytes(br#"{"inner":"#).unwrap_err();
+
+        assert!(matches!(rejection, SimdJsonRejection::Syntax(_)));
+        assert_eq!(rejection.into_response().status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn data_error_is_unprocessable_with_path() {
+        let rejection =
+            Json::<Payload>::from_bytes(br#"{"inner":{"id":"one"}}"#).unwrap_err();
+
+        let SimdJsonRejection::Data(err) = &rejection else {
+            panic!("expected a data error, got {rejection:?}");
+        };
+        assert_eq!(err.path().to_string(), "inner.id");
+        assert!(rejection.body_text().contains("inner.id"));
+        assert_eq!(
+            rejection.into_response().status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_content_type_is_unsupported_media_type() {
+        let req = Request::new(Body::from(r#"{"inner":{"id":1}}"#));
+        let rejection = Json::<Payload>::from_request(req, &()).await.unwrap_err();
+
+        assert!(matches!(rejection, SimdJsonRejection::Json(_)));
+        assert_eq!(
+            rejection.into_response().status(),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+    }
+
+    #[tokio::test]
+    async fn oversized_body_is_payload_too_large() {
+        // Larger than axum's default 2 MB body limit.
+        let req = json_request(vec![b' '; 3 * 1024 * 1024]);
+        let rejection = Json::<Payload>::from_request(req, &()).await.unwrap_err();
+
+        assert!(matches!(rejection, SimdJsonRejection::Bytes(_)));
+        assert_eq!(
+            rejection.into_response().status(),
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+    }
+
+    #[tokio::test]
+    async fn extracts_json_request() {
+        let req = json_request(r#"{"inner":{"id":1}}"#);
+        let Json(payload) = Json::<Payload>::from_request(req, &()).await.unwrap();
+        assert_eq!(payload.inner.id, 1);
+    }
+}
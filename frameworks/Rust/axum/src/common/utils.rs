@@ -1,23 +1,11 @@
-use axum::{
-    body::Bytes,
-    http::{header, HeaderValue, StatusCode},
-    response::{IntoResponse, Response},
-};
-use serde::Deserialize;
+use axum::http::StatusCode;
 
-#[derive(Debug, Deserialize)]
-pub struct Params {
-    q: Option<String>,
-}
+pub use tfb_common::query::Params;
 
 #[allow(dead_code)]
 #[inline(always)]
 pub fn parse_params(params: Params) -> usize {
-    params
-        .q
-        .and_then(|q| q.parse().ok())
-        .unwrap_or(1)
-        .clamp(1, 500)
+    params.count()
 }
 
 /// Utility function for mapping any error into a `500 Internal Server Error`
@@ -29,26 +17,3 @@ where
 {
     (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
 }
-
-#[derive(Clone, Copy, Debug)]
-pub struct Utf8Html<T>(pub T);
-
-impl<T> IntoResponse for Utf8Html<T>
-where
-    T: Into<Bytes>,
-{
-    fn into_response(self) -> Response {
-        let mut res = (StatusCode::OK, self.0.into()).into_response();
-        res.headers_mut().insert(
-            header::CONTENT_TYPE,
-            HeaderValue::from_static("text/html; charset=utf-8"),
-        );
-        res
-    }
-}
-
-impl<T> From<T> for Utf8Html<T> {
-    fn from(inner: T) -> Self {
-        Self(inner)
-    }
-}
@@ -1,8 +1,12 @@
-mod common;
+/// Of the shared modules, only the JSON extractor is needed without a database.
+#[cfg(feature = "simd-json")]
+mod common {
+    pub mod simd_json;
+}
 mod server;
 
 use axum::{response::IntoResponse, routing::get, Router};
-use common::models::Message;
+use tfb_common::Message;
 use dotenv::dotenv;
 use mimalloc::MiMalloc;
 
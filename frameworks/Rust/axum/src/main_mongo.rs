@@ -6,22 +6,20 @@ mod server;
 use std::time::Duration;
 
 use axum::{
-    extract::Query, http::StatusCode, response::IntoResponse, routing::get, Router,
+    extract::Query, http::StatusCode, response::{Html, IntoResponse}, routing::get, Router,
 };
 
 #[cfg(not(feature = "simd-json"))]
 use axum::Json;
 #[cfg(feature = "simd-json")]
 use common::simd_json::Json;
-use common::{
-    models::{FortuneInfo, World}, random_id
-};
 use dotenv::dotenv;
 use mongodb::{
     options::{ClientOptions, Compressor},
     Client,
 };
 use rand::{rngs::SmallRng, rng, SeedableRng};
+use tfb_common::{random_id, Fortune, World};
 use yarte::Template;
 use mimalloc::MiMalloc;
 
@@ -29,17 +27,19 @@ use mimalloc::MiMalloc;
 static GLOBAL: MiMalloc = MiMalloc;
 
 use common::{
+    cache::{self, cached_queries},
     get_env,
-    utils::{parse_params, Params, Utf8Html},
+    utils::{parse_params, Params},
 };
 use mongo::database::{
-    fetch_fortunes, find_world_by_id, find_worlds, update_worlds, DatabaseConnection,
+    fetch_all_worlds, fetch_fortunes, find_world_by_id, find_worlds, update_worlds,
+    DatabaseConnection,
 };
 
 #[derive(Template)]
 #[template(path = "fortunes.html.hbs")]
 pub struct FortunesTemplate<'a> {
-    pub fortunes: &'a Vec<FortuneInfo>,
+    pub fortunes: &'a Vec<Fortune>,
 }
 
 async fn db(DatabaseConnection(db): DatabaseConnection) -> impl IntoResponse {
@@ -93,17 +93,9 @@ async fn updates(
 async fn fortunes(DatabaseConnection(db): DatabaseConnection) -> impl IntoResponse {
     let fortunes = fetch_fortunes(db).await.expect("could not fetch fortunes");
 
-    let fortune_infos: Vec<FortuneInfo> = fortunes
-        .iter()
-        .map(|f| FortuneInfo {
-            id: f.id,
-            message: f.message.clone(),
-        })
-        .collect();
-
-    Utf8Html(
+    Html(
         FortunesTemplate {
-            fortunes: &fortune_infos,
+            fortunes: &fortunes,
         }
         .call()
         .expect("error rendering template"),
@@ -141,11 +133,15 @@ async fn serve_app() {
     let client = Client::with_options(client_options).unwrap();
     let database = client.database("hello_world");
 
+    // Prime the cache with all worlds, once for all runtimes
+    cache::preload(|| fetch_all_worlds(database.clone())).await;
+
     let app = Router::new()
         .route("/fortunes", get(fortunes))
         .route("/db", get(db))
         .route("/queries", get(queries))
         .route("/updates", get(updates))
+        .route("/cached-queries", get(cached_queries))
         .with_state(database);
 
     server::serve(app, Some(8000)).await
@@ -2,12 +2,13 @@ mod common;
 mod mongo_raw;
 mod server;
 
-use common::{models::World, random_id};
+use tfb_common::{random_id, World};
 use mongo_raw::database::{
-    find_world_by_id, find_worlds, update_worlds, DatabaseConnection,
+    fetch_all_worlds, find_world_by_id, find_worlds, update_worlds, DatabaseConnection,
 };
 
 use common::{
+    cache::{self, cached_queries},
     get_env,
     utils::{parse_params, Params},
 };
@@ -113,10 +114,14 @@ async fn serve_app() {
     let client = Client::with_options(client_options).unwrap();
     let database = client.database("hello_world");
 
+    // Prime the cache with all worlds, once for all runtimes
+    cache::preload(|| fetch_all_worlds(database.clone())).await;
+
     let app = Router::new()
         .route("/db", get(db))
         .route("/queries", get(queries))
         .route("/updates", get(updates))
+        .route("/cached-queries", get(cached_queries))
         .with_state(database);
 
     server::serve(app, Some(8000)).await
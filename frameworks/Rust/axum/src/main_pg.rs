@@ -2,10 +2,11 @@ mod common;
 mod pg;
 
 use axum::{
-    extract::Query, http::StatusCode, response::IntoResponse, routing::get, Router,
+    extract::Query, http::StatusCode, response::{Html, IntoResponse}, routing::get, Router,
 };
 use dotenv::dotenv;
 use rand::rng;
+use tfb_common::random_id;
 use yarte::Template;
 use mimalloc::MiMalloc;
 
@@ -20,8 +21,9 @@ use common::simd_json::Json;
 mod server;
 
 use common::{
-    get_env, random_id,
-    utils::{parse_params, Params, Utf8Html},
+    cache::{self, cached_queries},
+    get_env,
+    utils::{parse_params, Params},
 };
 use pg::database::{DatabaseConnection, PgConnection};
 use pg::models::Fortune;
@@ -62,7 +64,7 @@ async fn fortunes(DatabaseConnection(conn): DatabaseConnection) -> impl IntoResp
         .await
         .expect("error loading fortunes");
 
-    Utf8Html(
+    Html(
         FortunesTemplate {
             fortunes: &fortunes,
         }
@@ -92,11 +94,15 @@ async fn serve_app() {
     // Create shared database connection
     let pg_connection = PgConnection::connect(database_url).await;
 
+    // Prime the cache with all worlds, once for all runtimes
+    cache::preload(|| pg_connection.fetch_all_worlds()).await;
+
     let app = Router::new()
         .route("/fortunes", get(fortunes))
         .route("/db", get(db))
         .route("/queries", get(queries))
         .route("/updates", get(updates))
+        .route("/cached-queries", get(cached_queries))
         .with_state(pg_connection);
 
     server::serve_hyper(app, Some(8000)).await
@@ -2,7 +2,7 @@ mod common;
 mod pg_pool;
 
 use axum::{
-    extract::Query, http::StatusCode, response::IntoResponse, routing::get, Router,
+    extract::Query, http::StatusCode, response::{Html, IntoResponse}, routing::get, Router,
 };
 
 #[cfg(not(feature = "simd-json"))]
@@ -10,10 +10,11 @@ use axum::Json;
 #[cfg(feature = "simd-json")]
 use common::simd_json::Json;
 
-use common::{random_ids, SELECT_ALL_FORTUNES, SELECT_WORLD_BY_ID, UPDATE_WORLDS};
+use common::{SELECT_ALL_FORTUNES, SELECT_WORLD_BY_ID};
 use dotenv::dotenv;
 use futures_util::{stream::FuturesUnordered, TryStreamExt};
 use rand::{rngs::SmallRng, rng, SeedableRng};
+use tfb_common::{fortune, random_id, random_ids, update};
 use yarte::Template;
 use mimalloc::MiMalloc;
 
@@ -23,11 +24,13 @@ static GLOBAL: MiMalloc = MiMalloc;
 mod server;
 
 use common::{
-    get_env, random_id,
-    utils::{parse_params, Params, Utf8Html},
+    cache::{self, cached_queries},
+    get_env,
+    utils::{parse_params, Params},
 };
 use pg_pool::database::{
-    create_pool, fetch_all_fortunes, fetch_world_by_id, DatabaseClient, PgError,
+    create_pool, fetch_all_fortunes, fetch_all_worlds, fetch_world_by_id,
+    DatabaseClient, PgError,
 };
 use pg_pool::models::{Fortune, World};
 
@@ -75,14 +78,16 @@ async fn fortunes(DatabaseClient(client): DatabaseClient) -> impl IntoResponse {
         .await
         .expect("could not fetch fortunes");
 
-    fortunes.push(Fortune {
-        id: 0,
-        message: "Additional fortune added at request time.".to_string(),
-    });
+    fortune::prepare_with(
+        &mut fortunes,
+        |id, message| Fortune {
+            id,
+            message: message.to_string(),
+        },
+        |fortune| &fortune.message,
+    );
 
-    fortunes.sort_by(|a, b| a.message.cmp(&b.message));
-
-    Utf8Html(
+    Html(
         FortunesTemplate {
             fortunes: &fortunes,
         }
@@ -99,26 +104,20 @@ async fn updates(
 
     let mut rng = SmallRng::from_rng(&mut rng());
     let select = &client.prepare_cached(SELECT_WORLD_BY_ID).await.unwrap();
-    let update = &client.prepare_cached(UPDATE_WORLDS).await.unwrap();
+    let update = &client.prepare_cached(update::UPDATE_WORLDS).await.unwrap();
 
     // Select the random worlds.
     let future_worlds = FuturesUnordered::new();
     for id in random_ids(&mut rng, q) {
         future_worlds.push(fetch_world_by_id(&client, id, select));
     }
-    let worlds: Vec<World> = future_worlds.try_collect().await.unwrap();
-
-    let mut ids = Vec::with_capacity(q);
-    let mut nids = Vec::with_capacity(q);
-    let worlds: Vec<World> = worlds
-        .into_iter()
-        .map(|mut w| {
-            w.randomnumber = random_id(&mut rng);
-            ids.push(w.id);
-            nids.push(w.randomnumber);
-            w
-        })
-        .collect();
+    let mut worlds: Vec<World> = future_worlds.try_collect().await.unwrap();
+
+    for w in &mut worlds {
+        w.randomnumber = random_id(&mut rng);
+    }
+    let (ids, nids) =
+        update::dedup_updates_with(&mut worlds, |w| (w.id, &mut w.randomnumber));
 
     // Update the random worlds in the database.
     client.execute(update, &[&ids, &nids]).await.unwrap();
@@ -135,11 +134,17 @@ async fn main() {
 
     let pool = create_pool(database_url, max_pool_size).await;
 
+    // Prime the cache with all worlds
+    let client = pool.get().await.expect("could not get database connection");
+    cache::preload(|| fetch_all_worlds(&client)).await;
+    drop(client);
+
     let app = Router::new()
         .route("/fortunes", get(fortunes))
         .route("/db", get(db))
         .route("/queries", get(queries))
         .route("/updates", get(updates))
+        .route("/cached-queries", get(cached_queries))
         .with_state(pool);
 
     server::serve_hyper(app, Some(8000)).await
@@ -1,20 +1,20 @@
 mod common;
 mod sqlx;
 
-use std::{borrow::Cow, sync::Arc};
+use std::borrow::Cow;
 
 use ::sqlx::PgPool;
 use axum::{
     extract::{Query, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{Html, IntoResponse},
     routing::get,
     Router,
 };
 use dotenv::dotenv;
-use quick_cache::sync::Cache;
 use rand::{rngs::SmallRng, rng, SeedableRng};
 use sqlx::models::World;
+use tfb_common::{fortune, random_id, random_ids, update};
 use yarte::Template;
 use mimalloc::MiMalloc;
 
@@ -29,8 +29,9 @@ use common::simd_json::Json;
 mod server;
 
 use common::{
-    get_env, random_id, random_ids,
-    utils::{parse_params, Params, Utf8Html},
+    cache::{self, cached_queries},
+    get_env,
+    utils::{internal_error, parse_params, Params},
 };
 use sqlx::database::create_pool;
 use sqlx::models::Fortune;
@@ -78,14 +79,16 @@ async fn fortunes(State(AppState { db, .. }): State<AppState>) -> impl IntoRespo
         .await
         .expect("error loading Fortunes");
 
-    fortunes.push(Fortune {
-        id: 0,
-        message: Cow::Borrowed("Additional fortune added at request time."),
-    });
-
-    fortunes.sort_by(|a, b| a.message.cmp(&b.message));
+    fortune::prepare_with(
+        &mut fortunes,
+        |id, message| Fortune {
+            id,
+            message: Cow::Borrowed(message),
+        },
+        |fortune| &fortune.message,
+    );
 
-    Utf8Html(
+    Html(
         FortunesTemplate {
             fortunes: &fortunes,
         }
@@ -94,38 +97,58 @@ async fn fortunes(State(AppState { db, .. }): State<AppState>) -> impl IntoRespo
     )
 }
 
-async fn cache(
-    State(AppState { cache, .. }): State<AppState>,
+async fn updates(
+    State(AppState { db, .. }): State<AppState>,
     Query(params): Query<Params>,
-) -> impl IntoResponse {
-    let count = parse_params(params);
+) -> Result<impl IntoResponse, (StatusCode, String)> {
     let mut rng = SmallRng::from_rng(&mut rng());
-    let mut worlds: Vec<Option<World>> = Vec::with_capacity(count);
-    
+    let count = parse_params(params);
+    let mut conn = db.acquire().await.map_err(internal_error)?;
+    let mut worlds: Vec<World> = Vec::with_capacity(count);
+
+    // Select the random worlds.
     for id in random_ids(&mut rng, count) {
-        worlds.push(cache.get(&id));
+        let world: World = ::sqlx::query_as(common::SELECT_WORLD_BY_ID)
+            .bind(id)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(internal_error)?;
+        worlds.push(world);
     }
 
-    (StatusCode::OK, Json(worlds))
+    for world in &mut worlds {
+        world.random_number = random_id(&mut rng);
+    }
+    let (ids, nids) = update::dedup_updates_with(&mut worlds, |world| {
+        (world.id, &mut world.random_number)
+    });
+
+    // Update the random worlds in the database.
+    ::sqlx::query(update::UPDATE_WORLDS)
+        .bind(&ids)
+        .bind(&nids)
+        .execute(&mut *conn)
+        .await
+        .map_err(internal_error)?;
+
+    Ok((StatusCode::OK, Json(worlds)))
 }
 
-/// Pre-load the cache with all worlds.
-async fn preload_cache(AppState { db, cache }: &AppState) {
+/// Load all worlds for the cache.
+async fn fetch_all_worlds(
+    db: &PgPool,
+) -> Result<Vec<tfb_common::World>, ::sqlx::Error> {
     let worlds: Vec<World> = ::sqlx::query_as(common::SELECT_ALL_CACHED_WORLDS)
-        .fetch_all(&mut *db.acquire().await.unwrap())
-        .await
-        .expect("error loading worlds");
+        .fetch_all(&mut *db.acquire().await?)
+        .await?;
 
-    for world in worlds {
-        cache.insert(world.id, world);
-    }
+    Ok(worlds.into_iter().map(Into::into).collect())
 }
 
 /// Application state
 #[derive(Clone)]
 struct AppState {
     db: PgPool,
-    cache: Arc<Cache<i32, World>>,
 }
 
 #[tokio::main]
@@ -138,17 +161,17 @@ async fn main() {
 
     let state = AppState {
         db: create_pool(database_url, max_pool_size, min_pool_size).await,
-        cache: Arc::new(Cache::new(10_000))
     };
 
-    // Prime the cache with CachedWorld objects
-    preload_cache(&state).await;
+    // Prime the cache with all worlds
+    cache::preload(|| fetch_all_worlds(&state.db)).await;
 
     let app = Router::new()
         .route("/fortunes", get(fortunes))
         .route("/db", get(db))
         .route("/queries", get(queries))
-        .route("/cached-queries", get(cache))
+        .route("/updates", get(updates))
+        .route("/cached-queries", get(cached_queries))
         .with_state(state);
 
     server::serve_hyper(app, Some(8000)).await
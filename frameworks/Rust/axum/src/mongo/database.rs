@@ -5,7 +5,7 @@ use futures_util::{stream::FuturesUnordered, StreamExt, TryStreamExt};
 use mongodb::{bson::doc, Database};
 use rand::rngs::SmallRng;
 
-use crate::common::{models::{Fortune, World}, random_ids};
+use tfb_common::{random_ids, Fortune, World};
 
 pub struct DatabaseConnection(pub Database);
 
@@ -63,6 +63,13 @@ pub async fn find_worlds(db: Database, rng: &mut SmallRng, count: usize) -> Resu
     worlds
 }
 
+pub async fn fetch_all_worlds(db: Database) -> Result<Vec<World>, MongoError> {
+    let world_collection = db.collection::<World>("world");
+
+    let worlds: Vec<World> = world_collection.find(doc! {}).await?.try_collect().await?;
+    Ok(worlds)
+}
+
 pub async fn fetch_fortunes(db: Database) -> Result<Vec<Fortune>, MongoError> {
     let fortune_collection = db.collection::<Fortune>("fortune");
 
@@ -77,12 +84,7 @@ pub async fn fetch_fortunes(db: Database) -> Result<Vec<Fortune>, MongoError> {
         fortunes.push(doc.expect("could not load fortune"));
     }
 
-    fortunes.push(Fortune {
-        id: 0,
-        message: "Additional fortune added at request time.".to_string(),
-    });
-
-    fortunes.sort_by(|a, b| a.message.cmp(&b.message));
+    tfb_common::fortune::prepare(&mut fortunes);
     Ok(fortunes)
 }
 
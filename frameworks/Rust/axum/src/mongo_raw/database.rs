@@ -8,7 +8,7 @@ use mongodb::{
 };
 use rand::rngs::SmallRng;
 
-use crate::common::{models::World, random_ids};
+use tfb_common::{random_ids, World};
 
 pub struct DatabaseConnection(pub Database);
 
@@ -80,6 +80,25 @@ pub async fn find_worlds(db: Database, rng: &mut SmallRng, count: usize) -> Resu
     worlds
 }
 
+pub async fn fetch_all_worlds(db: Database) -> Result<Vec<World>, MongoError> {
+    let world_collection = db.collection::<RawDocumentBuf>("world");
+
+    let raws: Vec<RawDocumentBuf> =
+        world_collection.find(doc! {}).await?.try_collect().await?;
+
+    Ok(raws
+        .iter()
+        .map(|raw| World {
+            id: raw
+                .get_i32("id")
+                .expect("could not extract world id"),
+            random_number: raw
+                .get_i32("randomNumber")
+                .expect("could not extract world random number"),
+        })
+        .collect())
+}
+
 pub async fn update_worlds(
     db: Database,
     worlds: Vec<World>,
@@ -6,7 +6,9 @@ use rand::{rngs::SmallRng, rng, SeedableRng};
 use tokio::pin;
 use tokio_postgres::{connect, Client, NoTls, Statement};
 
-use crate::common::{self, random_id, random_ids};
+use tfb_common::{fortune, random_id, random_ids, update};
+
+use crate::common;
 
 use super::models::{Fortune, World};
 
@@ -53,7 +55,7 @@ impl PgConnection {
         // Prepare statements for the connection.
         let fortune = cl.prepare(common::SELECT_ALL_FORTUNES).await.unwrap();
         let world = cl.prepare(common::SELECT_WORLD_BY_ID).await.unwrap();
-        let updates = cl.prepare(common::UPDATE_WORLDS).await.unwrap();
+        let updates = cl.prepare(update::UPDATE_WORLDS).await.unwrap();
 
         Arc::new(PgConnection {
             client: cl,
@@ -92,16 +94,13 @@ impl PgConnection {
     pub async fn update_worlds(&self, num: usize) -> Result<Vec<World>, PgError> {
         let mut worlds = self.fetch_random_worlds(num).await?;
 
-        // Update the worlds with new random numbers
+        // Update the worlds with new random numbers.
         let mut rng = SmallRng::from_rng(&mut rng());
-        let mut ids = Vec::with_capacity(num);
-        let mut nids = Vec::with_capacity(num);
-
         for w in &mut worlds {
             w.randomnumber = random_id(&mut rng);
-            ids.push(&w.id);
-            nids.push(&w.randomnumber);
         }
+        let (ids, nids) =
+            update::dedup_updates_with(&mut worlds, |w| (w.id, &mut w.randomnumber));
 
         // Update the random worlds in the database.
         self.client
@@ -111,11 +110,20 @@ impl PgConnection {
         Ok(worlds)
     }
 
+    pub async fn fetch_all_worlds(&self) -> Result<Vec<tfb_common::World>, PgError> {
+        let rows = self
+            .client
+            .query(common::SELECT_ALL_CACHED_WORLDS, &[])
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| tfb_common::World::new(row.get(0), row.get(1)))
+            .collect())
+    }
+
     pub async fn fetch_all_fortunes(&self) -> Result<Vec<Fortune>, PgError> {
-        let mut fortunes = vec![Fortune {
-            id: 0,
-            message: Cow::Borrowed("Additional fortune added at request time."),
-        }];
+        let mut fortunes = Vec::with_capacity(13);
 
         let rows = self
             .client
@@ -131,7 +139,14 @@ impl PgConnection {
             });
         }
 
-        fortunes.sort_by(|it, next| it.message.cmp(&next.message));
+        fortune::prepare_with(
+            &mut fortunes,
+            |id, message| Fortune {
+                id,
+                message: Cow::Borrowed(message),
+            },
+            |fortune| &fortune.message,
+        );
         Ok(fortunes)
     }
 }
@@ -1,7 +1,7 @@
 use std::io;
 
 use crate::{
-    common::utils::internal_error,
+    common::{self, utils::internal_error},
     pg_pool::models::{Fortune, World},
 };
 use axum::{
@@ -77,6 +77,18 @@ pub async fn fetch_world_by_id(
     Ok(World::from_row(row).unwrap())
 }
 
+pub async fn fetch_all_worlds(
+    client: &Client,
+) -> Result<Vec<tfb_common::World>, PgError> {
+    let select = client.prepare_cached(common::SELECT_ALL_CACHED_WORLDS).await?;
+    let rows = client.query(&select, &[]).await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| tfb_common::World::new(row.get(0), row.get(1)))
+        .collect())
+}
+
 pub async fn fetch_all_fortunes(
     client: Client,
     select: &Statement,
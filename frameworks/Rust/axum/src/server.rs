@@ -1,7 +1,8 @@
 use std::{
-    future::Future,
+    future::{Future, IntoFuture},
     io,
     net::{Ipv4Addr, SocketAddr, TcpListener},
+    time::Duration,
 };
 
 use axum::{
@@ -12,10 +13,17 @@ use axum::{
 use hyper::body::Incoming;
 use hyper::Request;
 use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::{conn::auto, graceful::GracefulShutdown};
 use tower::Service;
 use tower_http::set_header::SetResponseHeaderLayer;
 
 use socket2::{Domain, Socket, Type};
+use tfb_common::shutdown::{self, DRAIN_TIMEOUT};
+
+/// Bounds of the pause after an accept error, doubled on every consecutive
+/// failure so that running out of file descriptors does not spin the loop.
+const ACCEPT_BACKOFF_MIN: Duration = Duration::from_millis(5);
+const ACCEPT_BACKOFF_MAX: Duration = Duration::from_secs(1);
 
 /// Reuse an existing listener, ensuring that the socket `backlog``
 /// is set to enable a higher number of pending connections.
@@ -52,9 +60,25 @@ pub async fn serve(app: Router<()>, port: Option<u16>) {
         server_header_value,
     ));
 
-    axum::serve(listener, app.into_make_service())
-        .await
-        .unwrap();
+    // The server waits for every connection to close once the signal fires, so
+    // that wait is bounded the same way as in `serve_hyper`.
+    let (signalled, on_signal) = tokio::sync::oneshot::channel();
+    let server = axum::serve(listener, app.into_make_service())
+        .with_graceful_shutdown(async move {
+            shutdown::signal().await;
+            let _ = signalled.send(());
+        })
+        .into_future();
+    tokio::pin!(server);
+
+    tokio::select! {
+        result = &mut server => return result.unwrap(),
+        _ = on_signal => {}
+    }
+    match tokio::time::timeout(DRAIN_TIMEOUT, server).await {
+        Ok(result) => result.unwrap(),
+        Err(_) => eprintln!("timed out draining connections after {DRAIN_TIMEOUT:?}"),
+    }
 }
 
 /// Build an Axum server using the lower-level Hyper APIs for more
@@ -63,6 +87,7 @@ pub async fn serve(app: Router<()>, port: Option<u16>) {
 /// * Disabling connection upgrades (websockets are not needed).
 /// * Setting TCP_NODELAY on the input stream.
 /// * Aggregating flushes to better support pipelined responses.
+/// * Draining live connections on shutdown instead of dropping them.
 ///
 /// See for more details:
 /// * https://github.com/tokio-rs/axum/blob/1ac617a1b540e8523347f5ee889d65cad9a45ec4/examples/serve-with-hyper/src/main.rs
@@ -79,34 +104,78 @@ pub async fn serve_hyper(app: Router<()>, port: Option<u16>) {
         server_header_value,
     ));
 
-    // Continuously accept new connections.
+    let mut builder = auto::Builder::new(TokioExecutor::new());
+    builder.http1().pipeline_flush(true);
+
+    let graceful = GracefulShutdown::new();
+    let signal = shutdown::signal();
+    tokio::pin!(signal);
+    let mut backoff = ACCEPT_BACKOFF_MIN;
+
+    // Accept new connections until a shutdown signal is received.
     loop {
-        let (socket, _remote_addr) = listener.accept().await.unwrap();
-        socket
-            .set_nodelay(true)
-            .expect("could not set TCP_NODELAY!");
+        let socket = tokio::select! {
+            _ = &mut signal => break,
+            accepted = listener.accept() => match accepted {
+                Ok((socket, _remote_addr)) => {
+                    backoff = ACCEPT_BACKOFF_MIN;
+                    socket
+                }
+                Err(error) => {
+                    if !is_connection_error(&error) {
+                        // Only the first error of a run is logged, a full
+                        // descriptor table would otherwise flood stderr.
+                        if backoff == ACCEPT_BACKOFF_MIN {
+                            eprintln!("accept error: {error}, backing off");
+                        }
+                        tokio::time::sleep(backoff).await;
+                        backoff = (backoff * 2).min(ACCEPT_BACKOFF_MAX);
+                    }
+                    continue;
+                }
+            },
+        };
+        // Best effort: the connection is served either way.
+        let _ = socket.set_nodelay(true);
 
         let tower_service = app.clone();
+        let hyper_service =
+            hyper::service::service_fn(move |request: Request<Incoming>| {
+                tower_service.clone().call(request)
+            });
+
+        let connection = builder
+            .serve_connection(TokioIo::new(socket), hyper_service)
+            .into_owned();
+        let connection = graceful.watch(connection);
         tokio::spawn(async move {
-            let socket = TokioIo::new(socket);
-
-            let hyper_service =
-                hyper::service::service_fn(move |request: Request<Incoming>| {
-                    tower_service.clone().call(request)
-                });
-
-            if (hyper_util::server::conn::auto::Builder::new(TokioExecutor::new())
-                .http1()
-                .pipeline_flush(true)
-                .serve_connection(socket, hyper_service)
-                .await)
-                .is_err()
-            {}
+            let _ = connection.await;
         });
     }
+
+    // Stop accepting, then let the live connections finish what they are doing.
+    drop(listener);
+    tokio::select! {
+        _ = graceful.shutdown() => {}
+        _ = tokio::time::sleep(DRAIN_TIMEOUT) => {
+            eprintln!("timed out draining connections after {DRAIN_TIMEOUT:?}");
+        }
+    }
+}
+
+/// Errors that only concern the connection being accepted, such as the peer
+/// resetting it before it was accepted. These are retried immediately.
+fn is_connection_error(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionReset
+    )
 }
 
-/// Start a single-threaded tokio runtime on multiple threads.
+/// Start a single-threaded tokio runtime on multiple threads, and wait for all of
+/// them to return once the servers they run have shut down.
 #[allow(dead_code)]
 pub fn start_tokio<Fut>(f: fn() -> Fut)
 where
@@ -117,14 +186,22 @@ where
         .build()
         .unwrap();
 
-    for _ in 1..num_cpus::get() {
-        std::thread::spawn(move || {
-            let rt = tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-                .unwrap();
-            rt.block_on(f());
-        });
-    }
+    let threads: Vec<_> = (1..num_cpus::get())
+        .map(|_| {
+            std::thread::spawn(move || {
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .unwrap();
+                rt.block_on(f());
+            })
+        })
+        .collect();
     rt.block_on(f());
+
+    for thread in threads {
+        if thread.join().is_err() {
+            eprintln!("a server runtime panicked");
+        }
+    }
 }
@@ -26,3 +26,9 @@ pub struct World {
     #[serde(rename = "randomNumber")]
     pub random_number: i32,
 }
+
+impl From<World> for tfb_common::World {
+    fn from(world: World) -> Self {
+        Self::new(world.id, world.random_number)
+    }
+}
@@ -0,0 +1,113 @@
+use std::convert::Infallible;
+
+use http::header::{CONTENT_LENGTH, CONTENT_TYPE, SERVER};
+use http::Response;
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Bytes;
+use serde::Serialize;
+use tfb_common::query;
+use tokio::sync::OnceCell;
+use tokio_postgres::Row;
+use tracing::info;
+
+use crate::db;
+use crate::{Error, Result, APPLICATION_JSON, SERVER_HEADER};
+
+const QUERY: &str = "SELECT id, randomnumber FROM world ORDER BY id";
+
+/// All worlds, indexed by `id - 1`.
+///
+/// Loaded once by whichever runtime starts first; the other runtimes wait for it before they
+/// start accepting connections.
+static CACHE: OnceCell<Box<[World]>> = OnceCell::const_new();
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct World {
+    id: i32,
+    randomnumber: i32,
+}
+
+impl From<&Row> for World {
+    fn from(row: &Row) -> Self {
+        World {
+            id: row.get(0),
+            randomnumber: row.get(1),
+        }
+    }
+}
+
+/// Loads the world table into the cache if it has not been loaded yet.
+///
+/// An empty table is an error, so that the server fails at startup rather than on every
+/// request.
+pub async fn preload() -> Result<()> {
+    CACHE
+        .get_or_try_init(|| async {
+            let db = db::pool().get().await?;
+            let statement = db.prepare_cached(QUERY).await?;
+            let rows = db.query(&statement, &[]).await?;
+            if rows.is_empty() {
+                return Err(Error::EmptyWorldTable);
+            }
+            info!("Loaded {} worlds into the cache", rows.len());
+            Ok::<_, Error>(rows.iter().map(World::from).collect())
+        })
+        .await?;
+    Ok(())
+}
+
+pub fn get(query: Option<&str>) -> Result<Response<BoxBody<Bytes, Infallible>>> {
+    let count = query.map_or(query::MIN_QUERIES, query::queries);
+
+    let cache = CACHE.get().expect("world cache is loaded before serving");
+    let worlds = (0..count)
+        .map(|_| cache[fastrand::usize(..cache.len())])
+        .collect::<Vec<_>>();
+    let json = serde_json::to_vec(&worlds)?;
+
+    Response::builder()
+        .header(SERVER, SERVER_HEADER.clone())
+        .header(CONTENT_TYPE, APPLICATION_JSON.clone())
+        .header(CONTENT_LENGTH, json.len())
+        .body(Full::from(json).boxed())
+        .map_err(Error::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::body;
+
+    fn worlds(response: Response<BoxBody<Bytes, Infallible>>) -> Vec<serde_json::Value> {
+        serde_json::from_slice(&body(response)).unwrap()
+    }
+
+    #[test]
+    fn serves_from_the_cache() {
+        let _ = CACHE.set(
+            (1..=10)
+                .map(|id| World {
+                    id,
+                    randomnumber: id * 10,
+                })
+                .collect(),
+        );
+
+        let response = get(Some("q=7")).unwrap();
+        assert_eq!(response.headers()[CONTENT_TYPE], APPLICATION_JSON);
+        let worlds = worlds(response);
+        assert_eq!(worlds.len(), 7);
+        for world in worlds {
+            let id = world["id"].as_i64().unwrap();
+            assert!((1..=10).contains(&id));
+            assert_eq!(world["randomnumber"], id * 10);
+        }
+
+        // the count is parsed and clamped like the other tests'
+        assert_eq!(self::worlds(get(None).unwrap()).len(), 1);
+        assert_eq!(self::worlds(get(Some("q=foo")).unwrap()).len(), 1);
+        assert_eq!(self::worlds(get(Some("q=0")).unwrap()).len(), 1);
+        assert_eq!(self::worlds(get(Some("q=501")).unwrap()).len(), 500);
+    }
+}
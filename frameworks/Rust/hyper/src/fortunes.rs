@@ -5,9 +5,10 @@ use http::Response;
 use http_body_util::combinators::BoxBody;
 use http_body_util::{BodyExt, Full};
 use hyper::body::Bytes;
+use tfb_common::fortune;
 use tokio_postgres::Row;
 
-use crate::db::POOL;
+use crate::db;
 use crate::{Error, SERVER_HEADER, TEXT_HTML};
 
 const QUERY: &str = "SELECT id, message FROM fortune";
@@ -38,16 +39,19 @@ pub async fn get() -> crate::Result<Response<BoxBody<Bytes, Infallible>>> {
 }
 
 async fn tell_fortune() -> crate::Result<Vec<Fortune>> {
-    let db = POOL.get().await?;
+    let db = db::pool().get().await?;
     let statement = db.prepare_cached(QUERY).await?;
     let rows = db.query(&statement, &[]).await?;
     let mut fortunes = rows.iter().map(Fortune::from).collect::<Vec<_>>();
 
-    fortunes.push(Fortune {
-        id: 0,
-        message: "Additional fortune added at request time.".to_string(),
-    });
-    fortunes.sort_by(|a, b| a.message.cmp(&b.message));
+    fortune::prepare_with(
+        &mut fortunes,
+        |id, message| Fortune {
+            id,
+            message: message.to_string(),
+        },
+        |fortune| &fortune.message,
+    );
 
     Ok(fortunes)
 }
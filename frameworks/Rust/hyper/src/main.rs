@@ -3,10 +3,11 @@ use std::net::{Ipv4Addr, SocketAddr};
 use std::{io, thread};
 
 use clap::{Parser, ValueEnum};
+use deadpool_postgres::PoolError;
 use http_body_util::combinators::BoxBody;
 use http_body_util::{BodyExt, Empty, Full};
 use hyper::body::{Bytes, Incoming};
-use hyper::header::{HeaderValue, SERVER};
+use hyper::header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE, SERVER};
 use hyper::server::conn::http1;
 use hyper::service::service_fn;
 use hyper::{Request, Response, StatusCode};
@@ -18,12 +19,14 @@ use tokio::net::TcpListener;
 use tokio::runtime;
 use tracing::{error, info};
 
+mod cached_queries;
 mod db;
 mod fortunes;
 mod json;
 mod multiple_queries;
 mod plaintext;
 mod single_query;
+mod updates;
 
 static SERVER_HEADER: HeaderValue = HeaderValue::from_static("hyper");
 static APPLICATION_JSON: HeaderValue = HeaderValue::from_static("application/json");
@@ -44,8 +47,45 @@ enum Error {
     Http(#[from] http::Error),
     #[error("Database pool error: {0}")]
     DbPool(#[from] deadpool_postgres::PoolError),
+    #[error("Database pool creation error: {0}")]
+    CreatePool(#[from] deadpool_postgres::CreatePoolError),
     #[error("Serde error: {0}")]
     Serde(#[from] serde_json::Error),
+    #[error("The world table is empty")]
+    EmptyWorldTable,
+}
+
+impl Error {
+    /// The status reported to the client when a handler fails with this error.
+    ///
+    /// Waiting longer than `--pool-timeout-ms` for a pooled connection is a capacity problem
+    /// rather than a bug, so it is reported as 503 to let the client back off and retry.
+    fn status(&self) -> StatusCode {
+        match self {
+            Error::DbPool(PoolError::Timeout(_) | PoolError::Closed) => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Converts the error into a response, so that the connection stays open instead of being
+    /// aborted by hyper.
+    ///
+    /// This only runs when a request has failed, so logging here stays off the hot path.
+    fn into_response(self) -> Response<BoxBody<Bytes, Infallible>> {
+        let status = self.status();
+        error!(status = status.as_u16(), error = %self, "request failed");
+
+        let body = status.canonical_reason().unwrap_or("Error");
+        let mut response = Response::new(Full::from(body).boxed());
+        *response.status_mut() = status;
+        let headers = response.headers_mut();
+        headers.insert(SERVER, SERVER_HEADER.clone());
+        headers.insert(CONTENT_TYPE, TEXT_PLAIN.clone());
+        headers.insert(CONTENT_LENGTH, HeaderValue::from(body.len()));
+        response
+    }
 }
 
 #[derive(Debug, Parser)]
@@ -64,6 +104,9 @@ struct Args {
     ///   runtime to use.
     #[arg(short, long, default_value_t = num_cpus::get())]
     threads: usize,
+
+    #[command(flatten)]
+    database: db::DbArgs,
 }
 
 #[derive(Clone, Debug, Default, Display, ValueEnum)]
@@ -79,7 +122,13 @@ fn main() -> Result<()> {
     // in the hot path code.
     tracing_subscriber::fmt().with_thread_ids(true).init();
 
-    let args = Args::parse();
+    let mut args = Args::parse();
+    if let Runtime::MultiThread = args.runtime {
+        // A single runtime has no use for more than one pool.
+        args.database.pool_strategy = db::PoolStrategy::Shared;
+    }
+    db::init(args.database)?;
+
     match args.runtime {
         Runtime::CurrentThread => run_current_thread(args.threads)?,
         Runtime::MultiThread => run_multi_thread(args.threads)?,
@@ -123,6 +172,8 @@ fn run_server(runtime: runtime::Runtime) -> Result<()> {
 }
 
 async fn serve(handle: &runtime::Handle) -> Result<()> {
+    cached_queries::preload().await?;
+
     let addr = SocketAddr::from((Ipv4Addr::UNSPECIFIED, 8080));
     let socket = create_socket(addr)?;
 
@@ -178,8 +229,14 @@ async fn accept_loop(handle: runtime::Handle, listener: TcpListener) -> Result<(
     }
 }
 
-/// Routes requests to the appropriate handler.
-async fn router(request: Request<Incoming>) -> Result<Response<BoxBody<Bytes, Infallible>>> {
+/// Routes requests to the appropriate handler, turning handler errors into error responses.
+async fn router(
+    request: Request<Incoming>,
+) -> Result<Response<BoxBody<Bytes, Infallible>>, Infallible> {
+    Ok(route(request).await.unwrap_or_else(Error::into_response))
+}
+
+async fn route(request: Request<Incoming>) -> Result<Response<BoxBody<Bytes, Infallible>>> {
     // The method is always GET, so we don't check it.
     match request.uri().path() {
         "/ping" => ping(),
@@ -187,6 +244,8 @@ async fn router(request: Request<Incoming>) -> Result<Response<BoxBody<Bytes, In
         "/db" => single_query::get().await,
         "/queries" => multiple_queries::get(request.uri().query()).await,
         "/fortunes" => fortunes::get().await,
+        "/updates" => updates::get(request.uri().query()).await,
+        "/cached-queries" => cached_queries::get(request.uri().query()),
         "/plaintext" => plaintext::get(),
         _ => not_found_error(),
     }
@@ -210,3 +269,52 @@ fn not_found_error() -> Result<Response<BoxBody<Bytes, Infallible>>> {
         .body(Empty::new().boxed())
         .map_err(Error::from)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(crate) fn body(response: Response<BoxBody<Bytes, Infallible>>) -> Bytes {
+        let collect = response.into_body().collect();
+        let runtime = runtime::Builder::new_current_thread().build().unwrap();
+        runtime.block_on(collect).unwrap().to_bytes()
+    }
+
+    #[test]
+    fn closed_pool_is_unavailable() {
+        let status = Error::DbPool(PoolError::Closed).status();
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn other_errors_are_internal() {
+        let errors = [
+            Error::EmptyWorldTable,
+            Error::DbPool(PoolError::NoRuntimeSpecified),
+            Error::Io(io::Error::other("reset")),
+            Error::Serde(serde_json::from_str::<()>("{").unwrap_err()),
+        ];
+        for error in errors {
+            assert_eq!(error.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    #[test]
+    fn error_response() {
+        let response = Error::DbPool(PoolError::Closed).into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let headers = response.headers();
+        assert_eq!(headers[SERVER], SERVER_HEADER);
+        assert_eq!(headers[CONTENT_TYPE], TEXT_PLAIN);
+        assert_eq!(headers[CONTENT_LENGTH], "19");
+        assert_eq!(body(response), "Service Unavailable");
+    }
+
+    #[test]
+    fn internal_error_response() {
+        let response = Error::EmptyWorldTable.into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body(response), "Internal Server Error");
+    }
+}
@@ -6,9 +6,10 @@ use http_body_util::combinators::BoxBody;
 use http_body_util::{BodyExt, Full};
 use hyper::body::Bytes;
 use serde::Serialize;
+use tfb_common::query;
 use tokio_postgres::Row;
 
-use crate::db::POOL;
+use crate::db;
 use crate::{Error, Result, APPLICATION_JSON, SERVER_HEADER};
 
 const QUERY: &str = "SELECT id, randomnumber FROM world WHERE id = $1";
@@ -29,11 +30,7 @@ impl From<Row> for World {
 }
 
 pub async fn get(query: Option<&str>) -> Result<Response<BoxBody<Bytes, Infallible>>> {
-    let count = query
-        .and_then(|query| query.strip_prefix("count="))
-        .and_then(|query| query.parse().ok())
-        .unwrap_or(1)
-        .clamp(1, 500);
+    let count = query.map_or(query::MIN_QUERIES, query::queries);
 
     let worlds = query_worlds(count).await?;
     let json = serde_json::to_vec(&worlds)?;
@@ -47,7 +44,7 @@ pub async fn get(query: Option<&str>) -> Result<Response<BoxBody<Bytes, Infallib
 }
 
 async fn query_worlds(count: usize) -> Result<Vec<World>> {
-    let db = POOL.get().await?;
+    let db = db::pool().get().await?;
     let statement = db.prepare_cached(QUERY).await?;
     let mut worlds = Vec::with_capacity(count);
     for _ in 0..count {
@@ -8,7 +8,7 @@ use hyper::body::Bytes;
 use serde::Serialize;
 use tokio_postgres::Row;
 
-use crate::db::POOL;
+use crate::db;
 use crate::{Error, Result, APPLICATION_JSON, SERVER_HEADER};
 
 static QUERY: &str = "SELECT id, randomnumber FROM world WHERE id = $1";
@@ -42,7 +42,7 @@ pub async fn get() -> Result<Response<BoxBody<Bytes, Infallible>>> {
 }
 
 async fn query_world(id: i32) -> Result<World, Error> {
-    let db = POOL.get().await?;
+    let db = db::pool().get().await?;
     let statement = db.prepare_cached(QUERY).await?;
     let row = db.query_one(&statement, &[&id]).await?;
     let world = World::from(row);
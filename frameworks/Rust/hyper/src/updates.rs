@@ -0,0 +1,107 @@
+use std::convert::Infallible;
+
+use http::header::{CONTENT_LENGTH, CONTENT_TYPE, SERVER};
+use http::Response;
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Bytes;
+use serde::Serialize;
+use tfb_common::query;
+use tfb_common::update::{dedup_updates_with, UPDATE_WORLDS};
+use tokio_postgres::Row;
+
+use crate::db;
+use crate::{Error, Result, APPLICATION_JSON, SERVER_HEADER};
+
+const QUERY: &str = "SELECT id, randomnumber FROM world WHERE id = $1";
+
+#[derive(Debug, Serialize)]
+pub struct World {
+    id: i32,
+    randomnumber: i32,
+}
+
+impl From<Row> for World {
+    fn from(row: Row) -> Self {
+        World {
+            id: row.get(0),
+            randomnumber: row.get(1),
+        }
+    }
+}
+
+pub async fn get(query: Option<&str>) -> Result<Response<BoxBody<Bytes, Infallible>>> {
+    let count = query.map_or(query::MIN_QUERIES, query::queries);
+
+    let worlds = update_worlds(count).await?;
+    let json = serde_json::to_vec(&worlds)?;
+
+    Response::builder()
+        .header(SERVER, SERVER_HEADER.clone())
+        .header(CONTENT_TYPE, APPLICATION_JSON.clone())
+        .header(CONTENT_LENGTH, json.len())
+        .body(Full::from(json).boxed())
+        .map_err(Error::from)
+}
+
+async fn update_worlds(count: usize) -> Result<Vec<World>> {
+    let db = db::pool().get().await?;
+    let statement = db.prepare_cached(QUERY).await?;
+    let update = db.prepare_cached(UPDATE_WORLDS).await?;
+
+    let mut worlds = Vec::with_capacity(count);
+    for _ in 0..count {
+        let id = fastrand::i32(1..=10_000);
+        let row = db.query_one(&statement, &[&id]).await?;
+        worlds.push(World::from(row));
+    }
+
+    let (ids, numbers) = renumber(&mut worlds);
+    db.execute(&update, &[&ids, &numbers]).await?;
+
+    Ok(worlds)
+}
+
+/// Gives every world a new random number, and returns the ids and numbers to bind to
+/// [`UPDATE_WORLDS`]. A world drawn more than once keeps a single number.
+fn renumber(worlds: &mut [World]) -> (Vec<i32>, Vec<i32>) {
+    for world in worlds.iter_mut() {
+        world.randomnumber = fastrand::i32(1..=10_000);
+    }
+    dedup_updates_with(worlds, |world| (world.id, &mut world.randomnumber))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn world(id: i32) -> World {
+        World {
+            id,
+            randomnumber: 0,
+        }
+    }
+
+    #[test]
+    fn renumbers_every_world() {
+        let mut worlds: Vec<World> = [5, 3, 5, 9, 3, 5].iter().copied().map(world).collect();
+        let (ids, numbers) = renumber(&mut worlds);
+
+        assert_eq!(ids, [3, 5, 9]);
+        // the response agrees with what is written to the table
+        for world in &worlds {
+            let i = ids.binary_search(&world.id).unwrap();
+            assert_eq!(world.randomnumber, numbers[i]);
+            assert!((1..=10_000).contains(&world.randomnumber));
+        }
+        // and keeps the order of the draws
+        let order: Vec<i32> = worlds.iter().map(|world| world.id).collect();
+        assert_eq!(order, [5, 3, 5, 9, 3, 5]);
+    }
+
+    #[test]
+    fn serializes_as_the_world_table() {
+        let json = serde_json::to_string(&[world(1)]).unwrap();
+        assert_eq!(json, r#"[{"id":1,"randomnumber":0}]"#);
+    }
+}
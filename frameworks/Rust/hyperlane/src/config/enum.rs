@@ -0,0 +1,8 @@
+use super::*;
+
+#[derive(Clone, Copy, Debug, Subcommand)]
+pub(crate) enum CliCommand {
+    /// Create the `World` and `Fortune` tables and fill them with the benchmark
+    /// data, then exit. Existing rows are kept, so it can be run repeatedly.
+    Seed,
+}
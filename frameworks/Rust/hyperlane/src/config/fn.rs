@@ -11,3 +11,9 @@ pub(crate) fn init_server_config() -> ServerConfig {
 pub(crate) fn init_request_config() -> RequestConfig {
     RequestConfig::low_security()
 }
+
+pub(crate) fn default_database_url() -> String {
+    format!(
+        "{DATABASE_TYPE}://{DATABASE_USER_NAME}:{DATABASE_USER_PASSWORD}@{DATABASE_HOST}:{DATABASE_PORT}/{DATABASE_NAME}"
+    )
+}
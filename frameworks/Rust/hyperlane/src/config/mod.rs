@@ -1,5 +1,8 @@
+mod r#enum;
 mod r#fn;
+mod r#static;
+mod r#struct;
 
-pub(crate) use r#fn::*;
+pub(crate) use {r#enum::*, r#fn::*, r#static::*, r#struct::*};
 
 use super::*;
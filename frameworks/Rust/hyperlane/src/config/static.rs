@@ -0,0 +1,3 @@
+use super::*;
+
+pub static CLI: Lazy<Cli> = Lazy::new(Cli::parse);
@@ -0,0 +1,13 @@
+use super::*;
+
+/// Command line arguments, each of which can also be set from the environment.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub(crate) struct Cli {
+    /// PostgreSQL connection URL.
+    #[arg(long, env = "POSTGRES_URL", default_value_t = default_database_url())]
+    pub(crate) database_url: String,
+
+    #[command(subcommand)]
+    pub(crate) command: Option<CliCommand>,
+}
@@ -0,0 +1,10 @@
+use super::*;
+
+/// Why a request could not be answered from the database.
+#[derive(Debug)]
+pub(crate) enum DbError {
+    Sqlx(sqlx::Error),
+    Task(JoinError),
+    /// A world id the cache does not hold.
+    CacheMiss(Queries),
+}
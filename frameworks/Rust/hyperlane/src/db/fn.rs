@@ -5,89 +5,72 @@ pub(crate) fn get_db_connection() -> &'static DbPoolConnection {
     &DB
 }
 
-#[cfg(feature = "dev")]
-pub(crate) async fn create_database() {
+/// Create the benchmark tables if they do not exist, and insert the worlds and
+/// reference fortunes that are missing. Worlds already there keep their number.
+pub(crate) async fn seed_db() -> DbResult<()> {
     let db_pool: &DbPoolConnection = get_db_connection();
-    let _ = db_query(&format!("CREATE DATABASE {DATABASE_NAME};"))
-        .execute(db_pool)
-        .await;
+    let mut transaction: Transaction<'_, Postgres> = db_pool.begin().await?;
+    db_query(
+        "CREATE TABLE IF NOT EXISTS World (
+            id integer NOT NULL PRIMARY KEY,
+            randomNumber integer NOT NULL DEFAULT 0
+        )",
+    )
+    .execute(&mut *transaction)
+    .await?;
+    db_query(
+        "CREATE TABLE IF NOT EXISTS Fortune (
+            id integer NOT NULL PRIMARY KEY,
+            message varchar(2048) NOT NULL
+        )",
+    )
+    .execute(&mut *transaction)
+    .await?;
+    db_query(
+        "INSERT INTO World (id, randomNumber)
+        SELECT id, floor(random() * $1 + 1)::integer FROM generate_series(1, $1) AS id
+        ON CONFLICT (id) DO NOTHING",
+    )
+    .bind(RANDOM_MAX)
+    .execute(&mut *transaction)
+    .await?;
+    let (ids, messages): (Vec<i32>, Vec<&str>) = REFERENCE_FORTUNES.iter().copied().unzip();
+    db_query(
+        "INSERT INTO Fortune (id, message)
+        SELECT * FROM UNNEST($1::integer[], $2::varchar[])
+        ON CONFLICT (id) DO UPDATE SET message = EXCLUDED.message",
+    )
+    .bind(ids)
+    .bind(messages)
+    .execute(&mut *transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(())
 }
 
-#[cfg(feature = "dev")]
-pub(crate) async fn create_table() {
+pub(crate) async fn init_cache() -> DbResult<Vec<QueryRow>> {
     let db_pool: &DbPoolConnection = get_db_connection();
-    let _ = db_query(&format!(
-        "CREATE TABLE IF NOT EXISTS {TABLE_NAME_WORLD} (
-            id SERIAL PRIMARY KEY, randomNumber INT NOT NULL
-        );"
-    ))
-    .execute(db_pool)
-    .await;
-    let _ = db_query(&format!(
-        "CREATE TABLE IF NOT EXISTS {TABLE_NAME_FORTUNE} (
-            id SERIAL PRIMARY KEY, message VARCHAR NOT NULL
-        );"
-    ))
-    .execute(db_pool)
-    .await;
-}
-
-#[cfg(feature = "dev")]
-pub(crate) async fn insert_records() {
-    let db_pool: &DbPoolConnection = get_db_connection();
-    let row: PgRow = db_query(&format!("SELECT COUNT(*) FROM {TABLE_NAME_WORLD}"))
-        .fetch_one(db_pool)
-        .await
-        .unwrap();
-    let count: i64 = row.get(0);
-    let limit: i64 = RANDOM_MAX as i64;
-    if count >= limit {
-        return;
-    }
-    let missing_count: i64 = limit - count;
-    let mut values: Vec<String> = Vec::new();
-    for _ in 0..missing_count {
-        let random_number: i32 = get_random_id();
-        values.push(format!("(DEFAULT, {random_number})"));
-    }
-    let sql: String = format!(
-        "INSERT INTO {TABLE_NAME_WORLD} (id, randomNumber) VALUES {}",
-        values.join(",")
-    );
-    let _ = db_query(&sql).execute(db_pool).await;
-    let mut values: Vec<String> = Vec::new();
-    for _ in 0..missing_count {
-        let random_number: i32 = get_random_id();
-        values.push(format!("(DEFAULT, {random_number})"));
-    }
-    let sql: String = format!(
-        "INSERT INTO {TABLE_NAME_FORTUNE} (id, message) VALUES {}",
-        values.join(",")
-    );
-    let _ = db_query(&sql).execute(db_pool).await;
+    let sql: String = format!("SELECT id, randomNumber FROM {TABLE_NAME_WORLD} ORDER BY id LIMIT {RANDOM_MAX}");
+    let rows: Vec<PgRow> = db_query(&sql).fetch_all(db_pool).await?;
+    let res: Vec<QueryRow> = rows
+        .iter()
+        .map(|row| QueryRow::new(row.get(KEY_ID), row.get(KEY_RANDOM_NUMBER)))
+        .collect();
+    Ok(res)
 }
 
-pub(crate) async fn init_cache() -> Vec<QueryRow> {
-    let mut res: Vec<QueryRow> = Vec::with_capacity(RANDOM_MAX as usize);
-    let db_pool: &DbPoolConnection = get_db_connection();
-    let sql: String = format!("SELECT id, randomNumber FROM {TABLE_NAME_WORLD} LIMIT {RANDOM_MAX}");
-    if let Ok(rows) = db_query(&sql).fetch_all(db_pool).await {
-        for row in rows {
-            let id: i32 = row.get(KEY_ID);
-            let random_number: i32 = row.get(KEY_RANDOM_NUMBER);
-            res.push(QueryRow::new(id, random_number));
-        }
-    }
-    res
+/// Look up a world in the cache, which is sorted by id.
+#[inline(always)]
+pub(crate) fn cached_world_row(id: Queries) -> DbResult<QueryRow> {
+    let cache: &[QueryRow] = CACHE.get().map_or(&[], Vec::as_slice);
+    cache
+        .binary_search_by_key(&id, |row| row.id)
+        .map(|index| cache[index])
+        .map_err(|_| DbError::CacheMiss(id))
 }
 
 pub(crate) async fn connection_db() -> DbPoolConnection {
-    let db_url: &str = match option_env!("POSTGRES_URL") {
-        Some(it) => it,
-        _ => &format!(
-            "{DATABASE_TYPE}://{DATABASE_USER_NAME}:{DATABASE_USER_PASSWORD}@{DATABASE_HOST}:{DATABASE_PORT}/{DATABASE_NAME}"
-        ),
-    };
+    let db_url: &str = &CLI.database_url;
     let thread_count: u32 = get_thread_count() as u32;
     let max_connections: u32 = (thread_count * 4).min(DB_MAX_CONNECTIONS);
     let min_connections: u32 = thread_count.max(1);
@@ -100,77 +83,85 @@ pub(crate) async fn connection_db() -> DbPoolConnection {
         .test_before_acquire(false)
         .connect(db_url)
         .await
-        .unwrap();
+        .unwrap_or_else(|error| panic!("cannot connect to {db_url}: {error}"));
     pool
 }
 
-pub(crate) async fn get_update_data(limit: Queries) -> (Vec<QueryRow>, Vec<i32>, Vec<i32>) {
-    let db_pool: &DbPoolConnection = get_db_connection();
+/// Select `limit` random worlds one by one on `connection` and give each a new
+/// random number.
+///
+/// Returns the response rows, then the ids to update, sorted and without
+/// duplicates, with their new numbers. A world drawn twice keeps a single new
+/// number, in the response as in the database.
+pub(crate) async fn get_update_data(
+    limit: Queries,
+    connection: &mut PgConnection,
+) -> DbResult<(Vec<QueryRow>, Vec<i32>, Vec<i32>)> {
     let mut query_res_list: Vec<QueryRow> = Vec::with_capacity(limit as usize);
-    let rows: Vec<QueryRow> = get_some_row_id(limit, db_pool).await;
-    let mut id_list: Vec<i32> = Vec::with_capacity(rows.len());
-    let mut random_numbers: Vec<i32> = Vec::with_capacity(rows.len());
-    for row in rows.iter() {
-        let new_random_number: i32 = get_random_id() as i32;
-        id_list.push(row.id);
-        random_numbers.push(new_random_number);
-        query_res_list.push(QueryRow::new(row.id, new_random_number));
+    for _ in 0..limit {
+        let row: QueryRow = query_world_row(&mut *connection, get_random_id()).await?;
+        query_res_list.push(QueryRow::new(row.id, get_random_id()));
     }
-    (query_res_list, id_list, random_numbers)
+    let (id_list, random_numbers): (Vec<i32>, Vec<i32>) =
+        dedup_updates_with(&mut query_res_list, |row| (row.id, &mut row.randomNumber));
+    Ok((query_res_list, id_list, random_numbers))
 }
 
+/// Connect and load the cache before serving. The server cannot answer the cached
+/// queries test without the worlds, so it exits if they cannot be read.
 pub(crate) async fn init_db() {
-    #[cfg(feature = "dev")]
-    {
-        create_database().await;
-        create_table().await;
-        insert_records().await;
-    }
     let _ = get_db_connection();
-    let _ = CACHE.first();
+    match init_cache().await {
+        Ok(cache) => {
+            let _ = CACHE.set(cache);
+        }
+        Err(error) => {
+            eprintln!("cannot load the cached worlds: {error}");
+            std::process::exit(1);
+        }
+    }
 }
 
-pub(crate) async fn random_world_row(db_pool: &DbPoolConnection) -> QueryRow {
+pub(crate) async fn random_world_row(db_pool: &DbPoolConnection) -> DbResult<QueryRow> {
     let random_id: Queries = get_random_id();
     query_world_row(db_pool, random_id).await
 }
 
-pub(crate) async fn query_world_row(db_pool: &DbPoolConnection, id: Queries) -> QueryRow {
+pub(crate) async fn query_world_row<'e, E>(executor: E, id: Queries) -> DbResult<QueryRow>
+where
+    E: Executor<'e, Database = Postgres>,
+{
     let sql: &str = "SELECT id, randomNumber FROM World WHERE id = $1";
-    if let Ok(rows) = db_query(sql).bind(id).fetch_one(db_pool).await {
-        let random_number: i32 = rows.get(KEY_RANDOM_NUMBER);
-        return QueryRow::new(id, random_number);
-    }
-    QueryRow::new(id, 1)
+    let row: PgRow = db_query(sql).bind(id).fetch_one(executor).await?;
+    let random_number: i32 = row.get(KEY_RANDOM_NUMBER);
+    Ok(QueryRow::new(id, random_number))
 }
 
-pub(crate) async fn update_world_rows(limit: Queries) -> Vec<QueryRow> {
-    let db_pool: &DbPoolConnection = get_db_connection();
-    let (data, id_list, random_numbers) = get_update_data(limit).await;
-    let sql: &str = "UPDATE World SET randomNumber = $1 WHERE id = $2";
-    let mut tasks: Vec<JoinHandle<_>> = Vec::with_capacity(limit as usize);
-    for (id, random_number) in id_list.into_iter().zip(random_numbers.into_iter()) {
-        let db_pool: Pool<Postgres> = db_pool.clone();
-        tasks.push(spawn(async move {
-            db_query(sql)
-                .bind(random_number)
-                .bind(id)
-                .execute(&db_pool)
-                .await
-        }));
-    }
-    join_all(tasks).await;
-    data
+/// Run the updates test on a single pooled connection: the selects one by one,
+/// then one statement updating every drawn world. Sorted ids make concurrent
+/// requests lock their rows in the same order, so they cannot deadlock.
+pub(crate) async fn update_world_rows(limit: Queries) -> DbResult<Vec<QueryRow>> {
+    let mut connection: PoolConnection<Postgres> = get_db_connection().acquire().await?;
+    let (data, id_list, random_numbers) = get_update_data(limit, &mut connection).await?;
+    db_query(UPDATE_WORLDS)
+        .bind(id_list)
+        .bind(random_numbers)
+        .execute(&mut *connection)
+        .await?;
+    Ok(data)
 }
 
-pub(crate) async fn all_world_row() -> Vec<PgRow> {
+pub(crate) async fn all_world_row() -> DbResult<Vec<PgRow>> {
     let db_pool: &DbPoolConnection = get_db_connection();
     let sql: String = format!("SELECT id, message FROM {TABLE_NAME_FORTUNE}");
-    let res: Vec<PgRow> = db_query(&sql).fetch_all(db_pool).await.unwrap_or_default();
-    res
+    let res: Vec<PgRow> = db_query(&sql).fetch_all(db_pool).await?;
+    Ok(res)
 }
 
-pub(crate) async fn get_some_row_id(limit: Queries, db_pool: &DbPoolConnection) -> Vec<QueryRow> {
+pub(crate) async fn get_some_row_id(
+    limit: Queries,
+    db_pool: &DbPoolConnection,
+) -> DbResult<Vec<QueryRow>> {
     let tasks: Vec<_> = (0..limit)
         .map(|_| {
             let db_pool: Pool<Postgres> = db_pool.clone();
@@ -180,9 +171,15 @@ pub(crate) async fn get_some_row_id(limit: Queries, db_pool: &DbPoolConnection)
             })
         })
         .collect();
-    join_all(tasks)
-        .await
-        .into_iter()
-        .filter_map(Result::ok)
-        .collect()
+    join_all(tasks).await.into_iter().map(|task| task?).collect()
+}
+
+/// Count a failed request and log it with the count so far. Only the 1st, 2nd,
+/// 4th, 8th... failures are logged, so that an outage does not flood the output.
+pub(crate) fn record_db_error(error: &DbError) -> usize {
+    let count: usize = DB_ERROR_COUNT.fetch_add(1, Ordering::Relaxed) + 1;
+    if count.is_power_of_two() {
+        eprintln!("{error} ({count} failed requests so far)");
+    }
+    count
 }
@@ -0,0 +1,43 @@
+use super::*;
+
+impl DbError {
+    /// `503` when the database cannot be reached, `500` when a query failed.
+    #[inline(always)]
+    pub(crate) fn status_code(&self) -> usize {
+        match self {
+            Self::Sqlx(
+                sqlx::Error::PoolTimedOut
+                | sqlx::Error::PoolClosed
+                | sqlx::Error::Io(_)
+                | sqlx::Error::Tls(_),
+            ) => 503,
+            _ => 500,
+        }
+    }
+}
+
+impl From<sqlx::Error> for DbError {
+    #[inline(always)]
+    fn from(error: sqlx::Error) -> Self {
+        Self::Sqlx(error)
+    }
+}
+
+impl From<JoinError> for DbError {
+    #[inline(always)]
+    fn from(error: JoinError) -> Self {
+        Self::Task(error)
+    }
+}
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Sqlx(error) => write!(f, "database error: {error}"),
+            Self::Task(error) => write!(f, "database task failed: {error}"),
+            Self::CacheMiss(id) => write!(f, "world {id} is not in the cache"),
+        }
+    }
+}
+
+impl std::error::Error for DbError {}
@@ -1,7 +1,9 @@
+mod r#enum;
 mod r#fn;
+mod r#impl;
 mod r#static;
 mod r#type;
 
-pub(crate) use {r#fn::*, r#static::*, r#type::*};
+pub(crate) use {r#enum::*, r#fn::*, r#static::*, r#type::*};
 
 use super::*;
@@ -1,4 +1,5 @@
 use super::*;
 
 pub static DB: Lazy<DbPoolConnection> = Lazy::new(|| block_on(async { connection_db().await }));
-pub static CACHE: Lazy<Vec<QueryRow>> = Lazy::new(|| block_on(async { init_cache().await }));
+pub static CACHE: OnceCell<Vec<QueryRow>> = OnceCell::new();
+pub static DB_ERROR_COUNT: AtomicUsize = AtomicUsize::new(0);
@@ -1,3 +1,4 @@
 use super::*;
 
 pub type DbPoolConnection = Pool<Postgres>;
+pub type DbResult<T> = Result<T, DbError>;
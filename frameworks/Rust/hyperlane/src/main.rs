@@ -7,28 +7,41 @@ mod utils;
 
 use {config::*, db::*, middleware::*, route::*, server::*, utils::*};
 
-use std::fmt;
+use std::{
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use {
+    clap::{Parser, Subcommand},
     futures::{executor::block_on, future::join_all},
     hyperlane::{
-        tokio::{spawn, task::JoinHandle},
+        tokio::{spawn, task::JoinError},
         *,
     },
     hyperlane_time::*,
-    once_cell::sync::Lazy,
+    once_cell::sync::{Lazy, OnceCell},
     rand::{RngExt, SeedableRng, rng, rngs::SmallRng},
     serde::*,
     serde_json::{Value, json},
     sqlx::{
-        Pool, Postgres, Row,
-        postgres::{PgPoolOptions, PgRow},
+        Executor, Pool, Postgres, Row, Transaction,
+        pool::PoolConnection,
+        postgres::{PgConnection, PgPoolOptions, PgRow},
         query as db_query,
     },
+    tfb_common::update::{UPDATE_WORLDS, dedup_updates_with},
 };
 
 #[tokio::main]
 async fn main() {
+    if let Some(CliCommand::Seed) = CLI.command {
+        if let Err(error) = seed_db().await {
+            eprintln!("seeding failed: {error}");
+            std::process::exit(1);
+        }
+        return;
+    }
     init_db().await;
     Server::default()
         .server_config(init_server_config())
@@ -36,11 +49,11 @@ async fn main() {
         .request_middleware::<RequestMiddleware>()
         .route::<PlaintextRoute>("/plaintext")
         .route::<JsonRoute>("/json")
-        .route::<CachedQueryRoute>("/cached-quer")
+        .route::<CachedQueryRoute>("/cached-queries")
         .route::<DbRoute>("/db")
-        .route::<QueryRoute>("/query")
+        .route::<QueryRoute>("/queries")
         .route::<FortunesRoute>("/fortunes")
-        .route::<UpdateRoute>("/upda")
+        .route::<UpdateRoute>("/updates")
         .run()
         .await
         .unwrap()
@@ -11,7 +11,6 @@ impl ServerHook for RequestMiddleware {
             .set_header(CONNECTION, KEEP_ALIVE)
             .set_header(SERVER, HYPERLANE)
             .set_header(DATE, gmt())
-            .set_status_code(200)
-            .set_header(CONTENT_TYPE, APPLICATION_JSON);
+            .set_status_code(200);
     }
 }
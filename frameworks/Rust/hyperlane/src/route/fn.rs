@@ -0,0 +1,50 @@
+use super::*;
+
+/// Serve the request that matched `R`, then every following request on the same
+/// keep-alive connection, and close the connection when the client stops sending
+/// or a response cannot be written.
+pub(crate) async fn serve<R: Render>(ctx: &mut Context) {
+    ctx.get_mut_response()
+        .set_header(CONTENT_TYPE, R::CONTENT_TYPE);
+    let mut failed: bool = false;
+    loop {
+        match R::render(ctx).await {
+            Ok(()) if failed => {
+                failed = false;
+                ctx.get_mut_response()
+                    .set_status_code(200)
+                    .set_header(CONTENT_TYPE, R::CONTENT_TYPE);
+            }
+            Ok(()) => {}
+            Err(error) => {
+                failed = true;
+                record_db_error(&error);
+                let status_code: usize = error.status_code();
+                let body: &str = if status_code == 503 {
+                    SERVICE_UNAVAILABLE_BODY
+                } else {
+                    INTERNAL_SERVER_ERROR_BODY
+                };
+                ctx.get_mut_response()
+                    .set_status_code(status_code)
+                    .set_header(CONTENT_TYPE, TEXT_PLAIN)
+                    .set_body(body);
+            }
+        }
+        if ctx.try_send().await.is_err() || ctx.http_from_stream().await.is_err() {
+            break;
+        }
+    }
+    ctx.set_closed(true);
+}
+
+/// The `key` query parameter clamped to `1..=ROW_LIMIT`, `1` when missing or invalid.
+#[inline(always)]
+pub(crate) fn get_queries(ctx: &Context, key: &str) -> Queries {
+    ctx.get_request()
+        .try_get_query(key)
+        .and_then(|queries| queries.parse::<Queries>().ok())
+        .unwrap_or_default()
+        .min(ROW_LIMIT as Queries)
+        .max(1)
+}
@@ -1,133 +1,57 @@
 use super::*;
 
-impl ServerHook for JsonRoute {
-    async fn new(_ctx: &mut Context) -> Self {
-        Self
-    }
+impl Render for JsonRoute {
+    const CONTENT_TYPE: &'static str = APPLICATION_JSON;
 
-    async fn handle(self, ctx: &mut Context) {
+    async fn render(ctx: &mut Context) -> DbResult<()> {
         let json: Value = json!({
             KEY_MESSAGE: RESPONSEDATA_STR
         });
         ctx.get_mut_response()
             .set_body(serde_json::to_vec(&json).unwrap_or_default());
-        if ctx.try_send().await.is_err() {
-            ctx.set_closed(true);
-            return;
-        }
-        while ctx.http_from_stream().await.is_ok() {
-            ctx.get_mut_response()
-                .set_body(serde_json::to_vec(&json).unwrap_or_default());
-            if ctx.try_send().await.is_err() {
-                ctx.set_closed(true);
-                return;
-            }
-        }
+        Ok(())
     }
 }
 
-impl ServerHook for PlaintextRoute {
-    async fn new(_ctx: &mut Context) -> Self {
-        Self
-    }
+impl Render for PlaintextRoute {
+    const CONTENT_TYPE: &'static str = TEXT_PLAIN;
 
-    async fn handle(self, ctx: &mut Context) {
-        ctx.get_mut_response()
-            .set_header(CONTENT_TYPE, TEXT_PLAIN)
-            .set_body(RESPONSEDATA_BIN);
-        if ctx.try_send().await.is_err() {
-            ctx.set_closed(true);
-            return;
-        }
-        while ctx.http_from_stream().await.is_ok() {
-            if ctx.try_send().await.is_err() {
-                ctx.set_closed(true);
-                break;
-            }
-        }
-        ctx.set_closed(true);
+    async fn render(ctx: &mut Context) -> DbResult<()> {
+        ctx.get_mut_response().set_body(RESPONSEDATA_BIN);
+        Ok(())
     }
 }
 
-impl ServerHook for DbRoute {
-    async fn new(_ctx: &mut Context) -> Self {
-        Self
-    }
+impl Render for DbRoute {
+    const CONTENT_TYPE: &'static str = APPLICATION_JSON;
 
-    async fn handle(self, ctx: &mut Context) {
+    async fn render(ctx: &mut Context) -> DbResult<()> {
         let db_connection: &DbPoolConnection = get_db_connection();
-        let query_row: QueryRow = random_world_row(db_connection).await;
+        let query_row: QueryRow = random_world_row(db_connection).await?;
         ctx.get_mut_response()
             .set_body(serde_json::to_vec(&query_row).unwrap_or_default());
-        if ctx.try_send().await.is_err() {
-            ctx.set_closed(true);
-            return;
-        }
-        while ctx.http_from_stream().await.is_ok() {
-            let query_row: QueryRow = random_world_row(db_connection).await;
-            ctx.get_mut_response()
-                .set_body(serde_json::to_vec(&query_row).unwrap_or_default());
-            if ctx.try_send().await.is_err() {
-                ctx.set_closed(true);
-                break;
-            }
-        }
-        ctx.set_closed(true);
+        Ok(())
     }
 }
 
-impl ServerHook for QueryRoute {
-    async fn new(_ctx: &mut Context) -> Self {
-        Self
-    }
+impl Render for QueryRoute {
+    const CONTENT_TYPE: &'static str = APPLICATION_JSON;
 
-    async fn handle(self, ctx: &mut Context) {
-        let queries: Queries = ctx
-            .get_request()
-            .try_get_query(QUERY_DB_QUERY_KEY)
-            .and_then(|queries| queries.parse::<Queries>().ok())
-            .unwrap_or_default()
-            .min(ROW_LIMIT as Queries)
-            .max(1);
+    async fn render(ctx: &mut Context) -> DbResult<()> {
+        let queries: Queries = get_queries(ctx, QUERY_DB_QUERY_KEY);
         let db_pool: &DbPoolConnection = get_db_connection();
-        let data: Vec<QueryRow> = get_some_row_id(queries, db_pool).await;
+        let data: Vec<QueryRow> = get_some_row_id(queries, db_pool).await?;
         ctx.get_mut_response()
             .set_body(serde_json::to_vec(&data).unwrap_or_default());
-        if ctx.try_send().await.is_err() {
-            ctx.set_closed(true);
-            return;
-        }
-        while ctx.http_from_stream().await.is_ok() {
-            let queries: Queries = ctx
-                .get_request()
-                .try_get_query(QUERY_DB_QUERY_KEY)
-                .and_then(|queries| queries.parse::<Queries>().ok())
-                .unwrap_or_default()
-                .min(ROW_LIMIT as Queries)
-                .max(1);
-            let data: Vec<QueryRow> = get_some_row_id(queries, db_pool).await;
-            ctx.get_mut_response()
-                .set_body(serde_json::to_vec(&data).unwrap_or_default());
-            if ctx.try_send().await.is_err() {
-                ctx.set_closed(true);
-                break;
-            }
-        }
-        ctx.set_closed(true);
+        Ok(())
     }
 }
 
-impl ServerHook for FortunesRoute {
-    async fn new(_ctx: &mut Context) -> Self {
-        Self
-    }
+impl Render for FortunesRoute {
+    const CONTENT_TYPE: &'static str = TEXT_HTML_UTF8;
 
-    async fn handle(self, ctx: &mut Context) {
-        ctx.get_mut_response().set_header(
-            CONTENT_TYPE,
-            ContentType::format_content_type_with_charset(TEXT_HTML, UTF8),
-        );
-        let all_rows: Vec<PgRow> = all_world_row().await;
+    async fn render(ctx: &mut Context) -> DbResult<()> {
+        let all_rows: Vec<PgRow> = all_world_row().await?;
         let mut fortunes_list: Vec<Fortunes> = all_rows
             .iter()
             .map(|row| {
@@ -142,111 +66,59 @@ impl ServerHook for FortunesRoute {
         fortunes_list.sort_by(|it, next| it.message.cmp(&next.message));
         let res: String = FortunesTemplate::new(fortunes_list).to_string();
         ctx.get_mut_response().set_body(&res);
-        if ctx.try_send().await.is_err() {
-            ctx.set_closed(true);
-            return;
-        }
-        while ctx.http_from_stream().await.is_ok() {
-            let all_rows: Vec<PgRow> = all_world_row().await;
-            let mut fortunes_list: Vec<Fortunes> = all_rows
-                .iter()
-                .map(|row| {
-                    let id: i32 = row.get(KEY_ID);
-                    Fortunes::new(id, row.get(KEY_MESSAGE))
-                })
-                .collect();
-            fortunes_list.push(Fortunes::new(
-                0,
-                "Additional fortune added at request time.".to_owned(),
-            ));
-            fortunes_list.sort_by(|it, next| it.message.cmp(&next.message));
-            let res: String = FortunesTemplate::new(fortunes_list).to_string();
-            ctx.get_mut_response().set_body(&res);
-            if ctx.try_send().await.is_err() {
-                ctx.set_closed(true);
-                break;
-            }
-        }
-        ctx.set_closed(true);
+        Ok(())
     }
 }
 
-impl ServerHook for UpdateRoute {
-    async fn new(_ctx: &mut Context) -> Self {
-        Self
-    }
+impl Render for UpdateRoute {
+    const CONTENT_TYPE: &'static str = APPLICATION_JSON;
 
-    async fn handle(self, ctx: &mut Context) {
-        let queries: Queries = ctx
-            .get_request()
-            .try_get_query(UPDATE_DB_QUERY_KEY)
-            .and_then(|queries| queries.parse::<Queries>().ok())
-            .unwrap_or_default()
-            .min(ROW_LIMIT as Queries)
-            .max(1);
-        let res: Vec<QueryRow> = update_world_rows(queries).await;
+    async fn render(ctx: &mut Context) -> DbResult<()> {
+        let queries: Queries = get_queries(ctx, UPDATE_DB_QUERY_KEY);
+        let res: Vec<QueryRow> = update_world_rows(queries).await?;
         ctx.get_mut_response()
             .set_body(serde_json::to_vec(&res).unwrap_or_default());
-        if ctx.try_send().await.is_err() {
-            ctx.set_closed(true);
-            return;
-        }
-        while ctx.http_from_stream().await.is_ok() {
-            let queries: Queries = ctx
-                .get_request()
-                .try_get_query(UPDATE_DB_QUERY_KEY)
-                .and_then(|queries| queries.parse::<Queries>().ok())
-                .unwrap_or_default()
-                .min(ROW_LIMIT as Queries)
-                .max(1);
-            let res: Vec<QueryRow> = update_world_rows(queries).await;
-            ctx.get_mut_response()
-                .set_body(serde_json::to_vec(&res).unwrap_or_default());
-            if ctx.try_send().await.is_err() {
-                ctx.set_closed(true);
-                break;
-            }
-        }
-        ctx.set_closed(true);
+        Ok(())
     }
 }
 
-impl ServerHook for CachedQueryRoute {
-    async fn new(_ctx: &mut Context) -> Self {
-        Self
-    }
+impl Render for CachedQueryRoute {
+    const CONTENT_TYPE: &'static str = APPLICATION_JSON;
 
-    async fn handle(self, ctx: &mut Context) {
-        let count: Queries = ctx
-            .get_request()
-            .try_get_query(CACHE_QUERY_KEY)
-            .and_then(|queries| queries.parse::<Queries>().ok())
-            .unwrap_or_default()
-            .min(ROW_LIMIT as Queries)
-            .max(1);
-        let res: Vec<&QueryRow> = CACHE.iter().take(count as usize).collect();
+    async fn render(ctx: &mut Context) -> DbResult<()> {
+        let count: Queries = get_queries(ctx, CACHE_QUERY_KEY);
+        let res: Vec<QueryRow> = (0..count)
+            .map(|_| cached_world_row(get_random_id()))
+            .collect::<DbResult<_>>()?;
         ctx.get_mut_response()
             .set_body(serde_json::to_vec(&res).unwrap_or_default());
-        if ctx.try_send().await.is_err() {
-            ctx.set_closed(true);
-            return;
-        }
-        while ctx.http_from_stream().await.is_ok() {
-            let count: Queries = ctx
-                .get_request()
-                .try_get_query(CACHE_QUERY_KEY)
-                .and_then(|queries| queries.parse::<Queries>().ok())
-                .unwrap_or_default()
-                .min(ROW_LIMIT as Queries)
-                .max(1);
-            let res: Vec<&QueryRow> = CACHE.iter().take(count as usize).collect();
-            ctx.get_mut_response()
-                .set_body(serde_json::to_vec(&res).unwrap_or_default());
-            if ctx.try_send().await.is_err() {
-                ctx.set_closed(true);
-                break;
-            }
-        }
-        ctx.set_closed(true);
+        Ok(())
     }
 }
+
+/// Every route hands its connection to [`serve`].
+macro_rules! impl_server_hook {
+    ($($route:ty),+ $(,)?) => {
+        $(
+            impl ServerHook for $route {
+                async fn new(_ctx: &mut Context) -> Self {
+                    Self
+                }
+
+                async fn handle(self, ctx: &mut Context) {
+                    serve::<Self>(ctx).await;
+                }
+            }
+        )+
+    };
+}
+
+impl_server_hook!(
+    JsonRoute,
+    PlaintextRoute,
+    DbRoute,
+    QueryRoute,
+    FortunesRoute,
+    UpdateRoute,
+    CachedQueryRoute,
+);
@@ -1,6 +1,8 @@
+mod r#fn;
 mod r#impl;
 mod r#struct;
+mod r#trait;
 
-pub(crate) use r#struct::*;
+pub(crate) use {r#fn::*, r#struct::*, r#trait::*};
 
 use super::*;
@@ -0,0 +1,11 @@
+use super::*;
+
+/// The per-request part of a route. [`serve`] runs the connection around it.
+pub(crate) trait Render {
+    /// `Content-Type` of every response of the route.
+    const CONTENT_TYPE: &'static str;
+
+    /// Set the response body for the request currently in `ctx`, or return the
+    /// database error that prevented it; [`serve`] turns it into an error response.
+    fn render(ctx: &mut Context) -> impl Future<Output = DbResult<()>> + Send;
+}
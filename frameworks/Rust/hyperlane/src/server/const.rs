@@ -17,4 +17,23 @@ pub(crate) const KEY_MESSAGE: &str = "message";
 pub(crate) const DB_MAX_CONNECTIONS: u32 = 100;
 pub(crate) const QUERY_DB_QUERY_KEY: &str = "q";
 pub(crate) const UPDATE_DB_QUERY_KEY: &str = "q";
-pub(crate) const CACHE_QUERY_KEY: &str = "c";
+pub(crate) const CACHE_QUERY_KEY: &str = "q";
+pub(crate) const TEXT_HTML_UTF8: &str = "text/html; charset=utf-8";
+pub(crate) const INTERNAL_SERVER_ERROR_BODY: &str = "Internal Server Error";
+pub(crate) const SERVICE_UNAVAILABLE_BODY: &str = "Service Unavailable";
+/// The fortunes every implementation serves, as created by the benchmark's database
+/// setup.
+pub(crate) const REFERENCE_FORTUNES: [(i32, &str); 12] = [
+    (1, "fortune: No such file or directory"),
+    (2, "A computer scientist is someone who fixes things that aren't broken."),
+    (3, "After enough decimal places, nobody gives a damn."),
+    (4, "A bad random number generator: 1, 1, 1, 1, 1, 4.33e+67, 1, 1, 1"),
+    (5, "A computer program does what you tell it to do, not what you want it to do."),
+    (6, "Emacs is a nice operating system, but I prefer UNIX. — Tom Christaensen"),
+    (7, "Any program that runs right is obsolete."),
+    (8, "A list is only as strong as its weakest link. — Donald Knuth"),
+    (9, "Feature: A bug with seniority."),
+    (10, "Computers make very fast, very accurate mistakes."),
+    (11, "<script>alert(\"This should not be displayed in a browser alert box.\");</script>"),
+    (12, "フレームワークのベンチマーク"),
+];
@@ -1,7 +1,5 @@
-pub mod models;
 pub mod utils;
 
-use rand::{Rng, RngCore, distr::Uniform, rngs::SmallRng};
 use std::{env, fmt::Debug, str::FromStr};
 
 #[allow(dead_code)]
@@ -23,16 +21,3 @@ where
         .parse::<T>()
         .unwrap_or_else(|_| panic!("could not parse {key}"))
 }
-
-#[allow(dead_code)]
-#[inline(always)]
-pub fn random_id(rng: &mut impl RngCore) -> i32 {
-    rng.random_range(1..=10_000) as i32
-}
-
-#[allow(dead_code)]
-#[inline(always)]
-pub fn random_ids(rng: &mut SmallRng, count: usize) -> impl Iterator<Item = i32> + use<'_> {
-    rng.sample_iter(Uniform::new_inclusive(1, 10_000).unwrap())
-        .take(count)
-}
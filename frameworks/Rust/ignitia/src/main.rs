@@ -1,7 +1,7 @@
 mod common;
 
-use common::models::Message;
 use ignitia::{Response, Router, Server};
+use tfb_common::Message;
 
 const HELLO_WORLD: &str = "Hello, World!";
 
@@ -3,8 +3,6 @@ mod mongo;
 
 use common::{
     get_env,
-    models::{FortuneInfo, World},
-    random_id,
     utils::{Params, parse_params},
 };
 use ignitia::{Query, Response, Router, Server, State};
@@ -15,12 +13,13 @@ use mongodb::{
 };
 use rand::{SeedableRng, rng, rngs::SmallRng};
 use std::time::Duration;
+use tfb_common::{Fortune, World, random_id};
 use yarte::Template;
 
 #[derive(Template)]
 #[template(path = "fortunes.html.hbs")]
 pub struct FortunesTemplate<'a> {
-    pub fortunes: &'a Vec<FortuneInfo>,
+    pub fortunes: &'a Vec<Fortune>,
 }
 
 async fn db(State(db): State<mongodb::Database>) -> Response {
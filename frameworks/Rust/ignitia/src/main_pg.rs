@@ -2,13 +2,14 @@ mod common;
 mod pg;
 
 use common::{
-    get_env, random_id,
+    get_env,
     utils::{Params, parse_params},
 };
 use ignitia::{Query, Response, Router, Server, State};
 use pg::database::PgConnection;
 use pg::models::Fortune;
 use rand::rng;
+use tfb_common::random_id;
 use yarte::Template;
 
 #[derive(Template)]
@@ -2,7 +2,7 @@ mod common;
 mod pg_pool;
 
 use common::{
-    SELECT_ALL_FORTUNES, SELECT_WORLD_BY_ID, UPDATE_WORLDS, get_env, random_id, random_ids,
+    SELECT_ALL_FORTUNES, SELECT_WORLD_BY_ID, UPDATE_WORLDS, get_env,
     utils::{Params, parse_params},
 };
 use futures_util::{TryStreamExt, stream::FuturesUnordered};
@@ -10,6 +10,7 @@ use ignitia::{Query, Response, Router, Server, State};
 use pg_pool::database::{create_pool, fetch_all_fortunes, fetch_world_by_id};
 use pg_pool::models::{Fortune, World};
 use rand::{SeedableRng, rng, rngs::SmallRng};
+use tfb_common::{fortune, random_id, random_ids};
 use yarte::Template;
 
 #[derive(Template)]
@@ -97,12 +98,14 @@ async fn fortunes(State(pool): State<deadpool_postgres::Pool>) -> Response {
 
             match fetch_all_fortunes(client, &select).await {
                 Ok(mut fortunes) => {
-                    fortunes.push(Fortune {
-                        id: 0,
-                        message: "Additional fortune added at request time.".to_string(),
-                    });
-
-                    fortunes.sort_by(|a, b| a.message.cmp(&b.message));
+                    fortune::prepare_with(
+                        &mut fortunes,
+                        |id, message| Fortune {
+                            id,
+                            message: message.to_string(),
+                        },
+                        |fortune| &fortune.message,
+                    );
 
                     let html = FortunesTemplate {
                         fortunes: &fortunes,
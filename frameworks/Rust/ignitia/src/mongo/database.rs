@@ -1,11 +1,8 @@
-use crate::common::{
-    models::{Fortune, FortuneInfo, World},
-    random_ids,
-};
 use futures_util::{StreamExt, TryStreamExt, stream::FuturesUnordered};
 use mongodb::{Database, bson::doc};
 use rand::rngs::SmallRng;
 use std::io;
+use tfb_common::{Fortune, World, random_ids};
 
 #[allow(dead_code)]
 #[derive(Debug)]
@@ -54,7 +51,7 @@ pub async fn find_worlds(
     worlds
 }
 
-pub async fn fetch_fortunes(db: Database) -> Result<Vec<FortuneInfo>, MongoError> {
+pub async fn fetch_fortunes(db: Database) -> Result<Vec<Fortune>, MongoError> {
     let fortune_collection = db.collection::<Fortune>("fortune");
 
     let mut fortune_cursor = fortune_collection
@@ -62,22 +59,13 @@ pub async fn fetch_fortunes(db: Database) -> Result<Vec<FortuneInfo>, MongoError
         .await
         .expect("fortunes could not be loaded");
 
-    let mut fortunes: Vec<FortuneInfo> = Vec::new();
+    let mut fortunes: Vec<Fortune> = Vec::new();
 
     while let Some(doc) = fortune_cursor.next().await {
-        let fortune = doc.expect("could not load fortune");
-        fortunes.push(FortuneInfo {
-            id: fortune.id,
-            message: fortune.message,
-        });
+        fortunes.push(doc.expect("could not load fortune"));
     }
 
-    fortunes.push(FortuneInfo {
-        id: 0,
-        message: "Additional fortune added at request time.".to_string(),
-    });
-
-    fortunes.sort_by(|a, b| a.message.cmp(&b.message));
+    tfb_common::fortune::prepare(&mut fortunes);
     Ok(fortunes)
 }
 
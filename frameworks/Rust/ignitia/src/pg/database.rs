@@ -1,8 +1,8 @@
 use super::models::{Fortune, World};
-use crate::common::{random_id, random_ids};
 use futures::StreamExt;
 use rand::{SeedableRng, rng, rngs::SmallRng};
 use std::{borrow::Cow, sync::Arc};
+use tfb_common::{fortune, random_id, random_ids};
 use tokio::pin;
 use tokio_postgres::{Client, NoTls, Statement, connect};
 
@@ -80,10 +80,7 @@ impl PgConnection {
     }
 
     pub async fn fetch_all_fortunes(&self) -> Result<Vec<Fortune>, tokio_postgres::Error> {
-        let mut fortunes = vec![Fortune {
-            id: 0,
-            message: Cow::Borrowed("Additional fortune added at request time."),
-        }];
+        let mut fortunes = Vec::with_capacity(13);
 
         let rows = self
             .client
@@ -98,7 +95,14 @@ impl PgConnection {
             });
         }
 
-        fortunes.sort_by(|it, next| it.message.cmp(&next.message));
+        fortune::prepare_with(
+            &mut fortunes,
+            |id, message| Fortune {
+                id,
+                message: Cow::Borrowed(message),
+            },
+            |fortune| &fortune.message,
+        );
         Ok(fortunes)
     }
 }
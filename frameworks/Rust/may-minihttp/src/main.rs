@@ -13,15 +13,8 @@ use smallvec::SmallVec;
 use yarte::{ywrite_html, Serialize};
 
 mod utils {
-    use atoi::FromRadix10;
-
-    pub fn get_query_param(query: &str) -> u16 {
-        let q = if let Some(pos) = query.find("?q") {
-            u16::from_radix_10(query.split_at(pos + 3).1.as_ref()).0
-        } else {
-            1
-        };
-        q.clamp(1, 500)
+    pub fn get_query_param(path: &str) -> u16 {
+        tfb_common::query::queries_in_path(path) as u16
     }
 }
 
@@ -204,11 +197,11 @@ impl PgConnection {
             id: r.get(0),
             message: r.get(1),
         }));
-        fortunes.push(Fortune {
-            id: 0,
-            message: "Additional fortune added at request time.",
-        });
-        fortunes.sort_by(|it, next| it.message.cmp(next.message));
+        tfb_common::fortune::prepare_with(
+            &mut fortunes,
+            |id, message| Fortune { id, message },
+            |fortune| fortune.message,
+        );
 
         let mut body = unsafe { std::ptr::read(buf) };
         ywrite_html!(body, "{{> fortune }}");
@@ -1,6 +1,6 @@
-use nanorand::{Rng, WyRand};
 use ntex::util::Bytes;
 use smallvec::SmallVec;
+use tfb_common::{fortune, WorldRng};
 use tokio_postgres::{connect, Client, Statement};
 use yarte::TemplateBytesTrait;
 
@@ -27,7 +27,7 @@ pub struct FortunesTemplate<'a, 'b> {
 /// Postgres interface
 pub struct PgConnection {
     cl: Client,
-    rng: WyRand,
+    rng: WorldRng,
     fortune: Statement,
     world: Statement,
     updates: Statement,
@@ -51,14 +51,14 @@ impl PgConnection {
             world,
             updates,
             fortune,
-            rng: WyRand::new(),
+            rng: WorldRng::new(),
         }
     }
 }
 
 impl PgConnection {
     pub async fn get_world(&self) -> Bytes {
-        let random_id = (self.rng.clone().generate::<u32>() % 10_000 + 1) as i32;
+        let random_id = self.rng.id();
         let row = self.cl.query_one(&self.world, &[&random_id]).await.unwrap();
 
         utils::buffer(256, |body| {
@@ -75,10 +75,8 @@ impl PgConnection {
     }
 
     pub async fn get_worlds(&self, num: usize) -> Bytes {
-        let mut rng = self.rng.clone();
         let mut queries = Vec::with_capacity(num);
-        (0..num).for_each(|_| {
-            let w_id = (rng.generate::<u32>() % 10_000 + 1) as i32;
+        self.rng.ids(num).for_each(|w_id| {
             queries.push(self.cl.query_one(&self.world, &[&w_id]));
         });
 
@@ -98,16 +96,14 @@ impl PgConnection {
     }
 
     pub async fn update(&self, num: usize) -> Bytes {
-        let mut rng = nanorand::tls_rng();
         let mut ids = Vec::with_capacity(num);
         let mut numbers = Vec::with_capacity(num);
         let mut worlds = SmallVec::<[_; 32]>::new();
         let mut queries = SmallVec::<[_; 32]>::new();
 
         (0..num).for_each(|_| {
-            let w_id = (rng.generate::<u32>() % 10_000 + 1) as i32;
-            ids.push(w_id);
-            numbers.push((rng.generate::<u32>() % 10_000 + 1) as i32);
+            ids.push(self.rng.id());
+            numbers.push(self.rng.id());
         });
         ids.sort();
 
@@ -135,15 +131,15 @@ impl PgConnection {
         let rows = self.cl.query_raw(&self.fortune, &[]).await.unwrap();
 
         let mut fortunes = Vec::with_capacity(16);
-        fortunes.push(Fortune {
-            id: 0,
-            message: "Additional fortune added at request time.",
-        });
         fortunes.extend(rows.iter().map(|row| Fortune {
             id: row.get(0),
             message: row.get(1),
         }));
-        fortunes.sort_by(|it, next| it.message.cmp(&next.message));
+        fortune::prepare_with(
+            &mut fortunes,
+            |id, message| Fortune { id, message },
+            |fortune| fortune.message,
+        );
 
         utils::buffer(4 * 1024, |body| {
             FortunesTemplate {
@@ -1,11 +1,11 @@
 #![allow(dead_code)]
-use std::{cell::Cell, cmp, io, io::Write, mem::MaybeUninit, slice::from_raw_parts_mut};
+use std::{cell::Cell, io, io::Write, mem::MaybeUninit, slice::from_raw_parts_mut};
 
-use atoi::FromRadix10;
 use ntex::http::{header::HeaderValue, HttpServiceConfig, KeepAlive};
 use ntex::util::{BufMut, Bytes, BytesMut};
 use ntex::{io::IoConfig, time::Seconds, SharedCfg};
 use sonic_rs::writer::WriteExt;
+use tfb_common::query;
 
 pub const HDR_SERVER: HeaderValue = HeaderValue::from_static("N");
 pub const HDR_JSON_CONTENT_TYPE: HeaderValue = HeaderValue::from_static("application/json");
@@ -49,13 +49,7 @@ pub fn db_config() -> SharedCfg {
 }
 
 pub fn get_query_param(query: Option<&str>) -> usize {
-    let query = query.unwrap_or("");
-    let q = if let Some(pos) = query.find('q') {
-        u16::from_radix_10(query.split_at(pos + 2).1.as_ref()).0
-    } else {
-        1
-    };
-    cmp::min(500, cmp::max(1, q) as usize)
+    query.map_or(query::MIN_QUERIES, query::queries)
 }
 
 pub fn buffer<F, R>(lw: usize, f: F) -> R
@@ -0,0 +1,44 @@
+use crate::{errors::AppError, models::World, postgres::Postgres};
+use std::sync::{Arc, OnceLock};
+use rand::{rng, rngs::SmallRng, distr::Uniform, RngExt, SeedableRng};
+
+static WORLDS: OnceLock<WorldCache> = OnceLock::new();
+
+/// Every world, sorted by id, loaded once for the whole process and shared by the
+/// cores through `Context`.
+#[derive(Clone)]
+pub struct WorldCache {
+    worlds: Arc<[World]>,
+}
+
+impl WorldCache {
+    /// The process-wide cache, loaded from `db` by the first caller.
+    pub async fn preload(db: &Postgres) -> Result<Self, tokio_postgres::Error> {
+        if let Some(cache) = WORLDS.get() {
+            return Ok(cache.clone());
+        }
+
+        let mut worlds = db.select_all_worlds().await?;
+        worlds.sort_unstable_by_key(|world| world.id);
+
+        // Cores starting together may all load it; the first one to finish wins.
+        Ok(WORLDS.get_or_init(|| Self { worlds: worlds.into() }).clone())
+    }
+
+    #[inline]
+    fn get(&self, id: i32) -> Option<World> {
+        self.worlds
+            .binary_search_by_key(&id, |world| world.id)
+            .ok()
+            .map(|i| self.worlds[i])
+    }
+
+    /// `n` random worlds, or an error if one of them is not in the cache.
+    pub fn random_worlds(&self, n: usize) -> Result<Vec<World>, AppError> {
+        let rng = SmallRng::from_rng(&mut rng());
+        rng.sample_iter(Uniform::new_inclusive(1, 10000).unwrap())
+            .take(n)
+            .map(|id| self.get(id).ok_or(AppError::CacheMiss(id)))
+            .collect()
+    }
+}
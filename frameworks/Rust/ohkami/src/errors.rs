@@ -1,7 +1,10 @@
-#![cfg(feature = "rt_tokio")]
+use crate::rt::ConnectError;
 
 pub enum AppError {
     Db(tokio_postgres::Error),
+    Connect(ConnectError),
+    Config(String),
+    CacheMiss(i32),
 }
 
 impl From<tokio_postgres::Error> for AppError {
@@ -10,16 +13,23 @@ impl From<tokio_postgres::Error> for AppError {
     }
 }
 
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Db(e)         => write!(f, "database error: {e}"),
+            Self::Connect(e)    => write!(f, "failed to connect database: {e}"),
+            Self::Config(e)     => f.write_str(e),
+            Self::CacheMiss(id) => write!(f, "world {id} is not in the cache"),
+        }
+    }
+}
+
 impl ohkami::IntoResponse for AppError {
     fn into_response(self) -> ohkami::Response {
-        match self {
-            Self::Db(_e) => {
-                #[cfg(debug_assertions)]
-                {
-                    eprintln!("{_e:?}");
-                }
-                ohkami::Response::InternalServerError()
-            }
+        #[cfg(debug_assertions)]
+        {
+            eprintln!("{self}");
         }
+        ohkami::Response::InternalServerError()
     }
 }
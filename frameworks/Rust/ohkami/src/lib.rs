@@ -1,39 +1,47 @@
+mod cache;
+mod errors;
 mod fangs;
 mod models;
-#[cfg(feature = "rt_tokio")] mod errors;
-#[cfg(feature = "rt_tokio")] mod postgres;
-#[cfg(feature = "rt_tokio")] mod templates;
+mod postgres;
+mod rt;
+mod templates;
 
 use {
+    cache::WorldCache,
+    errors::AppError,
     fangs::SetServer,
-    models::Message,
+    models::{Fortune, Message, World, WorldsMeta},
     ohkami::prelude::*,
-};
-#[cfg(feature = "rt_tokio")] use {
-    errors::AppError,
-    models::{Fortune, World, WorldsMeta},
     postgres::Postgres,
     templates::FortunesTemplate,
 };
 
 pub async fn ohkami() -> Ohkami {
+    let (db, cache) = connect().await.unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1)
+    });
+
     Ohkami::new((
         SetServer,
-        #[cfg(feature = "rt_tokio")]
-        Context::new(Postgres::new().await),
+        Context::new(db),
+        Context::new(cache),
         "/plaintext".GET(plaintext),
         "/json".GET(json_serialization),
-        #[cfg(feature = "rt_tokio")]
         "/db".GET(single_database_query),
-        #[cfg(feature = "rt_tokio")]
         "/queries".GET(multiple_database_query),
-        #[cfg(feature = "rt_tokio")]
         "/fortunes".GET(fortunes),
-        #[cfg(feature = "rt_tokio")]
         "/updates".GET(database_updates),
+        "/cached-queries".GET(cached_queries),
     ))
 }
 
+async fn connect() -> Result<(Postgres, WorldCache), AppError> {
+    let db = Postgres::connect().await?;
+    let cache = WorldCache::preload(&db).await?;
+    Ok((db, cache))
+}
+
 async fn plaintext() -> &'static str {
     "Hello, World!"
 }
@@ -44,7 +52,6 @@ async fn json_serialization() -> Json<Message> {
     })
 }
 
-#[cfg(feature = "rt_tokio")]
 async fn single_database_query(
     Context(db): Context<'_, Postgres>,
 ) -> Result<Json<World>, AppError> {
@@ -52,7 +59,6 @@ async fn single_database_query(
     Ok(Json(world))
 }
 
-#[cfg(feature = "rt_tokio")]
 async fn multiple_database_query(
     Query(q): Query<WorldsMeta<'_>>,
     Context(db): Context<'_, Postgres>,
@@ -62,7 +68,6 @@ async fn multiple_database_query(
     Ok(Json(worlds))
 }
 
-#[cfg(feature = "rt_tokio")]
 async fn fortunes(
     Context(db): Context<'_, Postgres>,
 ) -> Result<FortunesTemplate, AppError> {
@@ -75,7 +80,6 @@ async fn fortunes(
     Ok(FortunesTemplate { fortunes })
 }
 
-#[cfg(feature = "rt_tokio")]
 async fn database_updates(
     Query(q): Query<WorldsMeta<'_>>,
     Context(db): Context<'_, Postgres>,
@@ -84,3 +88,12 @@ async fn database_updates(
     let worlds = db.update_randomnumbers_of_n_worlds(n).await?;
     Ok(Json(worlds))
 }
+
+async fn cached_queries(
+    Query(q): Query<WorldsMeta<'_>>,
+    Context(cache): Context<'_, WorldCache>,
+) -> Result<Json<Vec<World>>, AppError> {
+    let n = q.parse();
+    let worlds = cache.random_worlds(n)?;
+    Ok(Json(worlds))
+}
@@ -5,9 +5,7 @@ pub struct Message {
     pub message: &'static str,
 }
 
-#[cfg(feature = "rt_tokio")]
 pub use db::*;
-#[cfg(feature = "rt_tokio")]
 mod db {
     use super::*;
 
@@ -16,7 +14,7 @@ mod db {
         pub message: String,
     }
 
-    #[derive(serde::Serialize)]
+    #[derive(Clone, Copy, serde::Serialize)]
     #[allow(non_snake_case)]
     pub struct World {
         pub id:           i32,
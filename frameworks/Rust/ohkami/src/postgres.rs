@@ -1,60 +1,112 @@
-#![cfg(feature = "rt_tokio")]
-
-use crate::models::{World, Fortune};
-use std::sync::Arc;
-use futures_util::stream::{StreamExt, TryStreamExt, FuturesUnordered};
+use crate::{errors::AppError, models::{World, Fortune}, rt};
+use std::{sync::{Arc, atomic::{AtomicUsize, Ordering}}, time::Duration};
+use futures_util::stream::{TryStreamExt, FuturesUnordered};
 use rand::{rng, rngs::SmallRng, distr::Uniform, RngExt, SeedableRng};
+use tfb_common::update::{dedup_updates_with, UPDATE_WORLDS};
 
+/// The connections opened by one `ohkami()` call, that is by one core, shared by
+/// its requests through `Context`. Each request runs on the next connection in turn.
 #[derive(Clone)]
 pub struct Postgres {
-    client:     Arc<tokio_postgres::Client>,
+    connections: Arc<[Connection]>,
+    next:        Arc<AtomicUsize>,
+}
+
+struct Connection {
+    client:     tokio_postgres::Client,
     statements: TechEmpowerStatements,
 }
 
-#[derive(Clone)]
 struct TechEmpowerStatements {
     select_world_by_id:  tokio_postgres::Statement,
+    select_all_worlds:   tokio_postgres::Statement,
     select_all_fortunes: tokio_postgres::Statement,
     update_worlds:       tokio_postgres::Statement,
 }
 
 impl Postgres {
-    pub async fn new() -> Self {
-        let (client, connection) = tokio_postgres::connect(
-            &std::env::var("DATABASE_URL").unwrap(),
-            tokio_postgres::NoTls
-        ).await.expect("failed to connect database");
-
-        tokio::spawn(async {
-            if let Err(e) = connection.await {
-                eprintln!("error in database connection: {e}");
+    const CONNECT_ATTEMPTS:  u32      = 10;
+    const FIRST_RETRY_DELAY: Duration = Duration::from_millis(100);
+    const MAX_RETRY_DELAY:   Duration = Duration::from_secs(5);
+
+    /// Open `DATABASE_CONNECTIONS` (default 1) connections to `DATABASE_URL`.
+    ///
+    /// The database may still be starting along with the server, so a failed
+    /// connection is retried with an exponential backoff before giving up.
+    pub async fn connect() -> Result<Self, AppError> {
+        let config: tokio_postgres::Config = std::env::var("DATABASE_URL")
+            .map_err(|_| AppError::Config(String::from("DATABASE_URL is not set")))?
+            .parse()
+            .map_err(|e| AppError::Config(format!("invalid DATABASE_URL: {e}")))?;
+
+        let size = match std::env::var("DATABASE_CONNECTIONS") {
+            Err(_) => 1,
+            Ok(n)  => n.parse::<usize>().ok().filter(|&n| n > 0).ok_or_else(|| {
+                AppError::Config(format!("invalid DATABASE_CONNECTIONS: {n}"))
+            })?,
+        };
+
+        let mut connections = Vec::with_capacity(size);
+        for _ in 0..size {
+            let client = Self::connect_with_retry(&config).await?;
+            connections.push(Connection::prepare(client).await?);
+        }
+
+        Ok(Self {
+            connections: connections.into(),
+            next:        Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    async fn connect_with_retry(config: &tokio_postgres::Config) -> Result<tokio_postgres::Client, AppError> {
+        let mut delay = Self::FIRST_RETRY_DELAY;
+        let mut attempt = 1;
+        loop {
+            match rt::connect(config).await {
+                Ok(client) => return Ok(client),
+                Err(e) if attempt < Self::CONNECT_ATTEMPTS => {
+                    eprintln!(
+                        "failed to connect database (attempt {attempt}/{}), retrying in {delay:?}: {e}",
+                        Self::CONNECT_ATTEMPTS
+                    );
+                    rt::sleep(delay).await;
+                    delay = (delay * 2).min(Self::MAX_RETRY_DELAY);
+                    attempt += 1;
+                }
+                Err(e) => return Err(AppError::Connect(e)),
             }
-        });
-        
+        }
+    }
+
+    #[inline]
+    fn connection(&self) -> &Connection {
+        let i = self.next.fetch_add(1, Ordering::Relaxed) % self.connections.len();
+        &self.connections[i]
+    }
+}
+
+impl Connection {
+    async fn prepare(client: tokio_postgres::Client) -> Result<Self, tokio_postgres::Error> {
         let statements = TechEmpowerStatements {
             select_world_by_id: client
                 .prepare("SELECT id, randomnumber FROM world WHERE id = $1 LIMIT 1")
-                .await
-                .unwrap(),
+                .await?,
+            select_all_worlds: client
+                .prepare("SELECT id, randomnumber FROM world")
+                .await?,
             select_all_fortunes: client
                 .prepare("SELECT id, message FROM fortune")
-                .await
-                .unwrap(),
+                .await?,
             update_worlds: client
-                .prepare("\
-                    UPDATE world SET randomnumber = new.randomnumber FROM ( \
-                        SELECT * FROM UNNEST($1::int[], $2::int[]) AS v(id, randomnumber) \
-                    ) AS new WHERE world.id = new.id \
-                ")
-                .await
-                .unwrap(),
+                .prepare(UPDATE_WORLDS)
+                .await?,
         };
 
-        Self { client: Arc::new(client), statements }
+        Ok(Self { client, statements })
     }
 }
 
-impl Postgres {
+impl Connection {
     const ID_RANGE: std::ops::RangeInclusive<i32> = 1..=10000;
     
     async fn select_random_world_by_id(&self, id: i32) -> Result<World, tokio_postgres::Error> {
@@ -67,34 +119,51 @@ impl Postgres {
             randomnumber: row.get(1),
         })
     }
+
+    async fn select_n_random_worlds(&self, n: usize) -> Result<Vec<World>, tokio_postgres::Error> {
+        let rng = SmallRng::from_rng(&mut rng());
+
+        let selects = FuturesUnordered::new();
+        for id in rng.sample_iter(Uniform::new_inclusive(Self::ID_RANGE.start(), Self::ID_RANGE.end()).unwrap()).take(n) {
+            selects.push(self.select_random_world_by_id(id))
+        }
+
+        selects.try_collect().await
+    }
 }
 
 impl Postgres {
     pub async fn select_random_world(&self) -> Result<World, tokio_postgres::Error> {
         let mut rng = SmallRng::from_rng(&mut rng());
-        self.select_random_world_by_id(rng.random_range(Self::ID_RANGE)).await
+        self.connection().select_random_world_by_id(rng.random_range(Connection::ID_RANGE)).await
     }
     
     pub async fn select_n_random_worlds(&self, n: usize) -> Result<Vec<World>, tokio_postgres::Error> {
-        let rng = SmallRng::from_rng(&mut rng());
+        self.connection().select_n_random_worlds(n).await
+    }
 
-        let selects = FuturesUnordered::new();
-        for id in rng.sample_iter(Uniform::new(Self::ID_RANGE.start(), Self::ID_RANGE.end()).unwrap()).take(n) {
-            selects.push(self.select_random_world_by_id(id))
-        }
+    pub async fn select_all_worlds(&self) -> Result<Vec<World>, tokio_postgres::Error> {
+        let connection = self.connection();
+        let rows = connection.client
+            .query(&connection.statements.select_all_worlds, &[])
+            .await?;
 
-        selects.try_collect().await
+        Ok(rows.iter().map(|row| World {
+            id:           row.get(0),
+            randomnumber: row.get(1),
+        }).collect())
     }
     
     pub async fn select_all_fortunes(&self) -> Result<Vec<Fortune>, tokio_postgres::Error> {
-        let mut rows = std::pin::pin!(self
+        let connection = self.connection();
+        let mut rows = std::pin::pin!(connection
             .client
-            .query_raw::<_, _, &[i32; 0]>(&self.statements.select_all_fortunes, &[])
+            .query_raw::<_, _, &[i32; 0]>(&connection.statements.select_all_fortunes, &[])
             .await?
         );
 
         let mut fortunes = Vec::new();
-        while let Some(row) = rows.next().await.transpose().unwrap() {
+        while let Some(row) = rows.try_next().await? {
             fortunes.push(Fortune {
                 id:      row.get(0),
                 message: row.get(1),
@@ -105,22 +174,21 @@ impl Postgres {
     }
     
     pub async fn update_randomnumbers_of_n_worlds(&self, n: usize) -> Result<Vec<World>, tokio_postgres::Error> {
+        let connection = self.connection();
         let rng = SmallRng::from_rng(&mut rng());
 
-        let mut worlds = self.select_n_random_worlds(n).await?;
+        let mut worlds = connection.select_n_random_worlds(n).await?;
 
-        let mut ids = Vec::with_capacity(n);
         let new_randomnumbers = rng
-            .sample_iter(Uniform::new(Self::ID_RANGE.start(), Self::ID_RANGE.end()).unwrap())
-            .take(n)
-            .collect::<Vec<_>>();
-        for i in 0..n {
-            worlds[i].randomnumber = new_randomnumbers[i];
-            ids.push(worlds[i].id);
+            .sample_iter(Uniform::new_inclusive(Connection::ID_RANGE.start(), Connection::ID_RANGE.end()).unwrap());
+        for (world, randomnumber) in worlds.iter_mut().zip(new_randomnumbers) {
+            world.randomnumber = randomnumber;
         }
 
-        self.client
-            .execute(&self.statements.update_worlds, &[&ids, &new_randomnumbers])
+        // A world drawn twice gets one new number, in the response as in the table.
+        let (ids, randomnumbers) = dedup_updates_with(&mut worlds, |world| (world.id, &mut world.randomnumber));
+        connection.client
+            .execute(&connection.statements.update_worlds, &[&ids, &randomnumbers])
             .await?;
 
         Ok(worlds)
@@ -0,0 +1,82 @@
+//! What the database layer needs from the async runtime: a connection to the
+//! server, a task driving it in the background, and a timer to wait before
+//! retrying.
+//!
+//! `tokio_postgres` only opens sockets on tokio. On the other runtimes the socket
+//! is an `async_io::Async<TcpStream>`, whose reactor runs independently of the
+//! executor, handed to `tokio_postgres` through the `tokio_util` compatibility
+//! layer.
+
+use tokio_postgres::{Client, Config, NoTls};
+
+pub type ConnectError = Box<dyn std::error::Error + Send + Sync>;
+
+#[cfg(feature = "rt_tokio")]
+pub async fn connect(config: &Config) -> Result<Client, ConnectError> {
+    let (client, connection) = config.connect(NoTls).await?;
+    spawn(async {
+        if let Err(e) = connection.await {
+            eprintln!("error in database connection: {e}");
+        }
+    });
+    Ok(client)
+}
+
+#[cfg(not(feature = "rt_tokio"))]
+pub async fn connect(config: &Config) -> Result<Client, ConnectError> {
+    use std::net::{TcpStream, ToSocketAddrs};
+    use tokio_postgres::config::Host;
+    use tokio_util::compat::FuturesAsyncReadCompatExt;
+
+    let host = match config.get_hosts() {
+        [Host::Tcp(host), ..] => host.as_str(),
+        _ => return Err("the database URL must name a TCP host".into()),
+    };
+    let port = config.get_ports().first().copied().unwrap_or(5432);
+    let addr = (host, port)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| format!("{host} did not resolve to any address"))?;
+
+    let stream = async_io::Async::<TcpStream>::connect(addr).await?;
+    stream.get_ref().set_nodelay(true)?;
+
+    let (client, connection) = config.connect_raw(stream.compat(), NoTls).await?;
+    spawn(async {
+        if let Err(e) = connection.await {
+            eprintln!("error in database connection: {e}");
+        }
+    });
+    Ok(client)
+}
+
+#[cfg(feature = "rt_tokio")]
+fn spawn(task: impl Future<Output = ()> + Send + 'static) {
+    tokio::spawn(task);
+}
+
+#[cfg(feature = "rt_smol")]
+fn spawn(task: impl Future<Output = ()> + Send + 'static) {
+    smol::spawn(task).detach();
+}
+
+#[cfg(feature = "rt_glommio")]
+fn spawn(task: impl Future<Output = ()> + Send + 'static) {
+    // Each shard connects from its own executor and keeps its connection there.
+    glommio::spawn_local(task).detach();
+}
+
+#[cfg(feature = "rt_nio")]
+fn spawn(task: impl Future<Output = ()> + Send + 'static) {
+    nio::spawn(task);
+}
+
+#[cfg(feature = "rt_tokio")]
+pub async fn sleep(duration: std::time::Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+#[cfg(not(feature = "rt_tokio"))]
+pub async fn sleep(duration: std::time::Duration) {
+    async_io::Timer::after(duration).await;
+}
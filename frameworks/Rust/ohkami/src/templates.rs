@@ -1,5 +1,3 @@
-#![cfg(feature = "rt_tokio")]
-
 use ohkami::{IntoResponse, Response};
 use yarte::Template;
 use crate::models::Fortune;
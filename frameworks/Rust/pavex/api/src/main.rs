@@ -3,11 +3,21 @@ static ALLOC: snmalloc_rs::SnMalloc = snmalloc_rs::SnMalloc;
 
 use application::{build_application_state, run};
 use server::server_builder;
+use tfb_pavex_bp::configuration::AppConfig;
 
 mod server;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    run(server_builder(), build_application_state().await).await?;
+    let config = match AppConfig::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    let server = server_builder(&config.server)?;
+    let application_state = build_application_state(config).await?;
+    run(server, application_state).await;
     Ok(())
 }
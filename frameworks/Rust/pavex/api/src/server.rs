@@ -1,26 +1,26 @@
-use std::{
-    io,
-    net::{Ipv4Addr, SocketAddr},
-};
+use std::{io, net::SocketAddr};
 
-use hyper::server::conn::AddrIncoming;
-use tokio::net::{TcpListener, TcpSocket};
+use pavex::server::{IncomingStream, Server, ServerConfiguration};
+use tfb_pavex_bp::configuration::ServerConfig;
+use tokio::net::TcpSocket;
 
-/// Build a new `hyper` server that listens for connections on port 8000.
-pub fn server_builder() -> hyper::server::Builder<AddrIncoming> {
-    let addr = SocketAddr::from((Ipv4Addr::UNSPECIFIED, 8000));
-    let listener = reuse_listener(addr).expect("couldn't bind to addr");
-    let incoming = AddrIncoming::from_listener(listener).unwrap();
+/// Build a new `pavex` server, running on `hyper` 1.x, that listens for connections on
+/// the configured address.
+pub fn server_builder(config: &ServerConfig) -> io::Result<Server> {
+    let incoming = reuse_listener(config.bind, config.backlog)?;
 
-    println!("Started hyper server at 8000");
+    let mut configuration = ServerConfiguration::new();
+    if let Some(workers) = config.workers {
+        configuration = configuration.set_n_workers(workers.get());
+    }
+
+    println!("Started pavex server at {}", config.bind);
 
-    hyper::Server::builder(incoming)
-        .http1_only(true)
-        .tcp_nodelay(true)
+    Ok(Server::new().set_config(configuration).listen(incoming))
 }
 
-/// Create a new `TcpListener` that can be reused by multiple processes (if supported by the OS).
-fn reuse_listener(addr: SocketAddr) -> io::Result<TcpListener> {
+/// Create a new listener that can be reused by multiple processes (if supported by the OS).
+fn reuse_listener(addr: SocketAddr, backlog: u32) -> io::Result<IncomingStream> {
     let socket = match addr {
         SocketAddr::V4(_) => TcpSocket::new_v4()?,
         SocketAddr::V6(_) => TcpSocket::new_v6()?,
@@ -34,6 +34,7 @@ fn reuse_listener(addr: SocketAddr) -> io::Result<TcpListener> {
     }
 
     socket.set_reuseaddr(true)?;
+    socket.set_nodelay(true)?;
     socket.bind(addr)?;
-    socket.listen(1024)
+    IncomingStream::try_from(socket.listen(backlog)?.into_std()?)
 }
@@ -1,100 +1,377 @@
-//! Do NOT edit this code.
-//! It was automatically generated by `pavex`.
-//! All manual edits will be lost next time the code is generated.
-#[allow(unused_imports)]
-use std as alloc;
+//! Written by hand after the code Pavex generates from `tfb_pavex_bp::blueprint`.
+//! Running `cargo px check` replaces it with the generated code.
+extern crate alloc;
 struct ServerState {
-    router: pavex_runtime::routing::Router<u32>,
-    #[allow(dead_code)]
+    router: matchit::Router<u32>,
     application_state: ApplicationState,
 }
-pub struct ApplicationState {}
-pub async fn build_application_state() -> crate::ApplicationState {
-    crate::ApplicationState {}
+pub struct ApplicationState {
+    s0: deadpool_postgres::Pool,
+    s1: tfb_pavex_bp::cache::WorldCache,
 }
-pub async fn run(
-    server_builder: pavex_runtime::hyper::server::Builder<
-        pavex_runtime::hyper::server::conn::AddrIncoming,
-    >,
+#[derive(Debug, thiserror::Error)]
+pub enum ApplicationStateError {
+    #[error(transparent)]
+    Pool(deadpool_postgres::CreatePoolError),
+    #[error(transparent)]
+    Load(tfb_pavex_bp::errors::DbError),
+}
+pub async fn build_application_state(
+    v0: tfb_pavex_bp::configuration::AppConfig,
+) -> Result<crate::ApplicationState, crate::ApplicationStateError> {
+    let v1 = tfb_pavex_bp::db::pool(&v0);
+    let v2 = match v1 {
+        Ok(ok) => ok,
+        Err(v2) => {
+            return {
+                let v3 = crate::ApplicationStateError::Pool(v2);
+                core::result::Result::Err(v3)
+            };
+        }
+    };
+    let v3 = tfb_pavex_bp::cache::WorldCache::load(&v2).await;
+    let v4 = match v3 {
+        Ok(ok) => ok,
+        Err(v4) => {
+            return {
+                let v5 = crate::ApplicationStateError::Load(v4);
+                core::result::Result::Err(v5)
+            };
+        }
+    };
+    let v5 = crate::ApplicationState { s0: v2, s1: v4 };
+    core::result::Result::Ok(v5)
+}
+pub fn run(
+    server_builder: pavex::server::Server,
     application_state: ApplicationState,
-) -> Result<(), pavex_runtime::Error> {
+) -> pavex::server::ServerHandle {
     let server_state = std::sync::Arc::new(ServerState {
-        router: build_router().map_err(pavex_runtime::Error::new)?,
+        router: build_router(),
         application_state,
     });
-    let make_service = pavex_runtime::hyper::service::make_service_fn(move |_| {
-        let server_state = server_state.clone();
-        async move {
-            Ok::<
-                _,
-                pavex_runtime::hyper::Error,
-            >(
-                pavex_runtime::hyper::service::service_fn(move |request| {
-                    let server_state = server_state.clone();
-                    async move {
-                        Ok::<
-                            _,
-                            pavex_runtime::hyper::Error,
-                        >(route_request(request, server_state).await)
-                    }
-                }),
-            )
-        }
-    });
-    server_builder.serve(make_service).await.map_err(pavex_runtime::Error::new)
+    server_builder.serve(route_request, server_state)
 }
-fn build_router() -> Result<
-    pavex_runtime::routing::Router<u32>,
-    pavex_runtime::routing::InsertError,
-> {
-    let mut router = pavex_runtime::routing::Router::new();
-    router.insert("/plaintext", 0u32)?;
-    Ok(router)
+fn build_router() -> matchit::Router<u32> {
+    let mut router = matchit::Router::new();
+    router.insert("/cached-queries", 0u32).unwrap();
+    router.insert("/db", 1u32).unwrap();
+    router.insert("/fortunes", 2u32).unwrap();
+    router.insert("/json", 3u32).unwrap();
+    router.insert("/plaintext", 4u32).unwrap();
+    router.insert("/queries", 5u32).unwrap();
+    router.insert("/updates", 6u32).unwrap();
+    router
 }
 async fn route_request(
-    request: pavex_runtime::http::Request<pavex_runtime::hyper::body::Body>,
+    request: http::Request<hyper::body::Incoming>,
+    _connection_info: Option<pavex::connection::ConnectionInfo>,
     server_state: std::sync::Arc<ServerState>,
-) -> pavex_runtime::response::Response {
-    let matched_route = match server_state.router.at(request.uri().path()) {
+) -> pavex::response::Response {
+    let (request_head, request_body) = request.into_parts();
+    #[allow(unused)]
+    let request_body = pavex::request::body::RawIncomingBody::from(request_body);
+    let request_head: pavex::request::RequestHead = request_head.into();
+    let matched_route = match server_state.router.at(request_head.target.path()) {
         Ok(m) => m,
         Err(_) => {
-            return pavex_runtime::response::Response::builder()
-                .status(pavex_runtime::http::StatusCode::NOT_FOUND)
-                .body(pavex_runtime::body::boxed(hyper::body::Body::empty()))
-                .unwrap();
+            let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter(
+                    vec![],
+                )
+                .into();
+            return route_0::entrypoint(&allowed_methods).await;
         }
     };
     let route_id = matched_route.value;
     #[allow(unused)]
-    let url_params: pavex_runtime::extract::route::RawRouteParams<'_, '_> = matched_route
+    let url_params: pavex::request::path::RawPathParams<'_, '_> = matched_route
         .params
         .into();
     match route_id {
         0u32 => {
-            match request.method() {
-                &pavex_runtime::http::Method::GET => route_handler_0().await,
+            match &request_head.method {
+                &pavex::http::Method::GET => route_1::entrypoint(&request_head, &server_state.application_state.s1).await,
+                _ => {
+                    let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
+                            pavex::http::Method::GET,
+                        ])
+                        .into();
+                    route_0::entrypoint(&allowed_methods).await
+                }
+            }
+        }
+        1u32 => {
+            match &request_head.method {
+                &pavex::http::Method::GET => route_2::entrypoint(&server_state.application_state.s0).await,
+                _ => {
+                    let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
+                            pavex::http::Method::GET,
+                        ])
+                        .into();
+                    route_0::entrypoint(&allowed_methods).await
+                }
+            }
+        }
+        2u32 => {
+            match &request_head.method {
+                &pavex::http::Method::GET => route_3::entrypoint(&server_state.application_state.s0).await,
                 _ => {
-                    pavex_runtime::response::Response::builder()
-                        .status(pavex_runtime::http::StatusCode::METHOD_NOT_ALLOWED)
-                        .header(pavex_runtime::http::header::ALLOW, "GET")
-                        .body(pavex_runtime::body::boxed(hyper::body::Body::empty()))
-                        .unwrap()
+                    let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
+                            pavex::http::Method::GET,
+                        ])
+                        .into();
+                    route_0::entrypoint(&allowed_methods).await
                 }
             }
         }
-        _ => {
-            pavex_runtime::response::Response::builder()
-                .status(pavex_runtime::http::StatusCode::NOT_FOUND)
-                .body(pavex_runtime::body::boxed(hyper::body::Body::empty()))
-                .unwrap()
+        3u32 => {
+            match &request_head.method {
+                &pavex::http::Method::GET => route_4::entrypoint().await,
+                _ => {
+                    let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
+                            pavex::http::Method::GET,
+                        ])
+                        .into();
+                    route_0::entrypoint(&allowed_methods).await
+                }
+            }
         }
+        4u32 => {
+            match &request_head.method {
+                &pavex::http::Method::GET => route_5::entrypoint().await,
+                _ => {
+                    let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
+                            pavex::http::Method::GET,
+                        ])
+                        .into();
+                    route_0::entrypoint(&allowed_methods).await
+                }
+            }
+        }
+        5u32 => {
+            match &request_head.method {
+                &pavex::http::Method::GET => route_6::entrypoint(&request_head, &server_state.application_state.s0).await,
+                _ => {
+                    let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
+                            pavex::http::Method::GET,
+                        ])
+                        .into();
+                    route_0::entrypoint(&allowed_methods).await
+                }
+            }
+        }
+        6u32 => {
+            match &request_head.method {
+                &pavex::http::Method::GET => route_7::entrypoint(&request_head, &server_state.application_state.s0).await,
+                _ => {
+                    let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
+                            pavex::http::Method::GET,
+                        ])
+                        .into();
+                    route_0::entrypoint(&allowed_methods).await
+                }
+            }
+        }
+        i => unreachable!("Unknown route id: {}", i),
     }
 }
-pub async fn route_handler_0() -> http::Response<
-    http_body::combinators::BoxBody<bytes::Bytes, pavex_runtime::Error>,
-> {
-    let v0 = tfb_pavex_bp::plaintext();
-    <http::Response<
-        http_body::combinators::BoxBody<bytes::Bytes, pavex_runtime::Error>,
-    > as pavex_runtime::response::IntoResponse>::into_response(v0)
+pub mod route_0 {
+    pub async fn entrypoint(
+        s_0: &pavex::router::AllowedMethods,
+    ) -> pavex::response::Response {
+        let response = handler(s_0).await;
+        let response = post_processing_0(response).await;
+        response
+    }
+    async fn handler(v0: &pavex::router::AllowedMethods) -> pavex::response::Response {
+        let v1 = pavex::router::default_fallback(v0).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v1)
+    }
+    async fn post_processing_0(
+        v0: pavex::response::Response,
+    ) -> pavex::response::Response {
+        let v1 = tfb_pavex_bp::routes::set_server(v0);
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v1)
+    }
+}
+pub mod route_1 {
+    pub async fn entrypoint<'a>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: &'a tfb_pavex_bp::cache::WorldCache,
+    ) -> pavex::response::Response {
+        let response = handler(s_0, s_1).await;
+        let response = post_processing_0(response).await;
+        response
+    }
+    async fn handler(
+        v0: &pavex::request::RequestHead,
+        v1: &tfb_pavex_bp::cache::WorldCache,
+    ) -> pavex::response::Response {
+        let v2 = tfb_pavex_bp::routes::cached_queries(v0, v1);
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
+    }
+    async fn post_processing_0(
+        v0: pavex::response::Response,
+    ) -> pavex::response::Response {
+        let v1 = tfb_pavex_bp::routes::set_server(v0);
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v1)
+    }
+}
+pub mod route_2 {
+    pub async fn entrypoint(
+        s_0: &deadpool_postgres::Pool,
+    ) -> pavex::response::Response {
+        let response = handler(s_0).await;
+        let response = post_processing_0(response).await;
+        response
+    }
+    async fn handler(
+        v0: &deadpool_postgres::Pool,
+    ) -> pavex::response::Response {
+        let v1 = tfb_pavex_bp::routes::db(v0).await;
+        let v2 = match v1 {
+            Ok(ok) => ok,
+            Err(v2) => {
+                let v3 = tfb_pavex_bp::errors::DbError::to_response(&v2);
+                return <pavex::response::Response as pavex::response::IntoResponse>::into_response(
+                    v3,
+                );
+            }
+        };
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
+    }
+    async fn post_processing_0(
+        v0: pavex::response::Response,
+    ) -> pavex::response::Response {
+        let v1 = tfb_pavex_bp::routes::set_server(v0);
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v1)
+    }
+}
+pub mod route_3 {
+    pub async fn entrypoint(
+        s_0: &deadpool_postgres::Pool,
+    ) -> pavex::response::Response {
+        let response = handler(s_0).await;
+        let response = post_processing_0(response).await;
+        response
+    }
+    async fn handler(
+        v0: &deadpool_postgres::Pool,
+    ) -> pavex::response::Response {
+        let v1 = tfb_pavex_bp::routes::fortunes(v0).await;
+        let v2 = match v1 {
+            Ok(ok) => ok,
+            Err(v2) => {
+                let v3 = tfb_pavex_bp::errors::DbError::to_response(&v2);
+                return <pavex::response::Response as pavex::response::IntoResponse>::into_response(
+                    v3,
+                );
+            }
+        };
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
+    }
+    async fn post_processing_0(
+        v0: pavex::response::Response,
+    ) -> pavex::response::Response {
+        let v1 = tfb_pavex_bp::routes::set_server(v0);
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v1)
+    }
+}
+pub mod route_4 {
+    pub async fn entrypoint() -> pavex::response::Response {
+        let response = handler().await;
+        let response = post_processing_0(response).await;
+        response
+    }
+    async fn handler() -> pavex::response::Response {
+        let v0 = tfb_pavex_bp::routes::json();
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v0)
+    }
+    async fn post_processing_0(
+        v0: pavex::response::Response,
+    ) -> pavex::response::Response {
+        let v1 = tfb_pavex_bp::routes::set_server(v0);
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v1)
+    }
+}
+pub mod route_5 {
+    pub async fn entrypoint() -> pavex::response::Response {
+        let response = handler().await;
+        let response = post_processing_0(response).await;
+        response
+    }
+    async fn handler() -> pavex::response::Response {
+        let v0 = tfb_pavex_bp::routes::plaintext();
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v0)
+    }
+    async fn post_processing_0(
+        v0: pavex::response::Response,
+    ) -> pavex::response::Response {
+        let v1 = tfb_pavex_bp::routes::set_server(v0);
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v1)
+    }
+}
+pub mod route_6 {
+    pub async fn entrypoint<'a>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: &'a deadpool_postgres::Pool,
+    ) -> pavex::response::Response {
+        let response = handler(s_0, s_1).await;
+        let response = post_processing_0(response).await;
+        response
+    }
+    async fn handler(
+        v0: &pavex::request::RequestHead,
+        v1: &deadpool_postgres::Pool,
+    ) -> pavex::response::Response {
+        let v2 = tfb_pavex_bp::routes::queries(v0, v1).await;
+        let v3 = match v2 {
+            Ok(ok) => ok,
+            Err(v3) => {
+                let v4 = tfb_pavex_bp::errors::DbError::to_response(&v3);
+                return <pavex::response::Response as pavex::response::IntoResponse>::into_response(
+                    v4,
+                );
+            }
+        };
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v3)
+    }
+    async fn post_processing_0(
+        v0: pavex::response::Response,
+    ) -> pavex::response::Response {
+        let v1 = tfb_pavex_bp::routes::set_server(v0);
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v1)
+    }
+}
+pub mod route_7 {
+    pub async fn entrypoint<'a>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: &'a deadpool_postgres::Pool,
+    ) -> pavex::response::Response {
+        let response = handler(s_0, s_1).await;
+        let response = post_processing_0(response).await;
+        response
+    }
+    async fn handler(
+        v0: &pavex::request::RequestHead,
+        v1: &deadpool_postgres::Pool,
+    ) -> pavex::response::Response {
+        let v2 = tfb_pavex_bp::routes::updates(v0, v1).await;
+        let v3 = match v2 {
+            Ok(ok) => ok,
+            Err(v3) => {
+                let v4 = tfb_pavex_bp::errors::DbError::to_response(&v3);
+                return <pavex::response::Response as pavex::response::IntoResponse>::into_response(
+                    v4,
+                );
+            }
+        };
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v3)
+    }
+    async fn post_processing_0(
+        v0: pavex::response::Response,
+    ) -> pavex::response::Response {
+        let v1 = tfb_pavex_bp::routes::set_server(v0);
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v1)
+    }
 }
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use tfb_common::World;
+
+use crate::db::{self, Pool};
+use crate::errors::DbError;
+
+/// Every world, sorted by id, loaded once when the application state is built.
+#[derive(Clone)]
+pub struct WorldCache {
+    worlds: Arc<[World]>,
+}
+
+impl WorldCache {
+    /// Singleton constructor: the server does not start if the worlds cannot be loaded.
+    pub async fn load(pool: &Pool) -> Result<WorldCache, DbError> {
+        let client = pool.get().await?;
+        let mut worlds = db::select_all_worlds(&client).await?;
+        worlds.sort_unstable_by_key(|world| world.id);
+        Ok(WorldCache {
+            worlds: worlds.into(),
+        })
+    }
+
+    #[inline]
+    pub fn get(&self, id: i32) -> Option<World> {
+        self.worlds
+            .binary_search_by_key(&id, |world| world.id)
+            .ok()
+            .map(|index| self.worlds[index])
+    }
+}
@@ -0,0 +1,74 @@
+use deadpool_postgres::{Client, Config, CreatePoolError, PoolConfig, Runtime};
+use tfb_common::update::{dedup_updates, UPDATE_WORLDS};
+use tfb_common::{Fortune, World, WorldRng};
+use tokio_postgres::NoTls;
+
+use crate::configuration::AppConfig;
+
+pub use deadpool_postgres::Pool;
+
+const SELECT_WORLD: &str = "SELECT id, randomnumber FROM world WHERE id = $1";
+const SELECT_ALL_WORLDS: &str = "SELECT id, randomnumber FROM world";
+const SELECT_FORTUNES: &str = "SELECT id, message FROM fortune";
+
+/// Singleton constructor of the connection pool, shared by every worker.
+pub fn pool(config: &AppConfig) -> Result<Pool, CreatePoolError> {
+    let config = Config {
+        url: Some(config.database.url.clone()),
+        pool: config
+            .database
+            .pool_size
+            .map(|size| PoolConfig::new(size.get())),
+        ..Default::default()
+    };
+    config.create_pool(Some(Runtime::Tokio1), NoTls)
+}
+
+pub async fn select_world(client: &Client, id: i32) -> Result<World, tokio_postgres::Error> {
+    let statement = client.prepare_cached(SELECT_WORLD).await?;
+    let row = client.query_one(&statement, &[&id]).await?;
+    Ok(World::new(row.get(0), row.get(1)))
+}
+
+pub async fn select_random_worlds(
+    client: &Client,
+    rng: &WorldRng,
+    count: usize,
+) -> Result<Vec<World>, tokio_postgres::Error> {
+    let statement = client.prepare_cached(SELECT_WORLD).await?;
+    let mut worlds = Vec::with_capacity(count);
+    for id in rng.ids(count) {
+        let row = client.query_one(&statement, &[&id]).await?;
+        worlds.push(World::new(row.get(0), row.get(1)));
+    }
+    Ok(worlds)
+}
+
+pub async fn select_all_worlds(client: &Client) -> Result<Vec<World>, tokio_postgres::Error> {
+    let rows = client.query(SELECT_ALL_WORLDS, &[]).await?;
+    Ok(rows
+        .iter()
+        .map(|row| World::new(row.get(0), row.get(1)))
+        .collect())
+}
+
+pub async fn select_fortunes(client: &Client) -> Result<Vec<Fortune>, tokio_postgres::Error> {
+    let statement = client.prepare_cached(SELECT_FORTUNES).await?;
+    let rows = client.query(&statement, &[]).await?;
+    Ok(rows
+        .iter()
+        .map(|row| Fortune::new(row.get(0), row.get::<_, String>(1)))
+        .collect())
+}
+
+/// Store the new number of every world. A world drawn twice keeps the first of its numbers,
+/// in `worlds` as in the table.
+pub async fn update_worlds(
+    client: &Client,
+    worlds: &mut [World],
+) -> Result<(), tokio_postgres::Error> {
+    let statement = client.prepare_cached(UPDATE_WORLDS).await?;
+    let (ids, numbers) = dedup_updates(worlds);
+    client.execute(&statement, &[&ids, &numbers]).await?;
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use pavex::response::Response;
+
+/// Why a request could not be answered from the database.
+#[derive(Debug, thiserror::Error)]
+pub enum DbError {
+    #[error("failed to get a database connection")]
+    Pool(#[from] deadpool_postgres::PoolError),
+    #[error("database query failed")]
+    Query(#[from] tokio_postgres::Error),
+}
+
+impl DbError {
+    /// Error handler of the database routes: `503` if no connection could be
+    /// obtained, `500` if a query failed.
+    pub fn to_response(&self) -> Response {
+        match self {
+            DbError::Pool(_) => Response::service_unavailable(),
+            DbError::Query(_) => Response::internal_server_error(),
+        }
+    }
+}
@@ -1,21 +1,31 @@
-use pavex_builder::{f, router::GET, Blueprint};
-use pavex_runtime::{
-    http::HeaderValue,
-    response::{IntoResponse, Response},
-};
+use pavex::blueprint::{router::GET, Blueprint};
+use pavex::{f, t};
+
+pub mod cache;
+pub mod configuration;
+pub mod db;
+pub mod errors;
+pub mod routes;
 
 /// Return the application blueprint that will be used by `pavex`
 /// to generate the application runtime code.
 pub fn blueprint() -> Blueprint {
     let mut bp = Blueprint::new();
-    bp.route(GET, "/plaintext", f!(crate::plaintext));
-    bp
-}
+    bp.prebuilt(t!(crate::configuration::AppConfig));
+    bp.singleton(f!(crate::db::pool));
+    bp.singleton(f!(crate::cache::WorldCache::load));
+    bp.post_process(f!(crate::routes::set_server));
 
-pub fn plaintext() -> Response {
-    let mut response = "Hello, World!".into_response();
-    response
-        .headers_mut()
-        .insert("Server", HeaderValue::from_static("pavex"));
-    response
+    bp.route(GET, "/plaintext", f!(crate::routes::plaintext));
+    bp.route(GET, "/json", f!(crate::routes::json));
+    bp.route(GET, "/db", f!(crate::routes::db))
+        .error_handler(f!(crate::errors::DbError::to_response));
+    bp.route(GET, "/queries", f!(crate::routes::queries))
+        .error_handler(f!(crate::errors::DbError::to_response));
+    bp.route(GET, "/fortunes", f!(crate::routes::fortunes))
+        .error_handler(f!(crate::errors::DbError::to_response));
+    bp.route(GET, "/updates", f!(crate::routes::updates))
+        .error_handler(f!(crate::errors::DbError::to_response));
+    bp.route(GET, "/cached-queries", f!(crate::routes::cached_queries));
+    bp
 }
@@ -0,0 +1,79 @@
+use pavex::http::{header::SERVER, HeaderValue};
+use pavex::request::RequestHead;
+use pavex::response::body::{Html, Json};
+use pavex::response::Response;
+use serde::Serialize;
+use tfb_common::{fortune, query, Message, WorldRng};
+
+use crate::cache::WorldCache;
+use crate::db::{self, Pool};
+use crate::errors::DbError;
+
+/// Post-processing middleware adding the `Server` header to every response.
+pub fn set_server(response: Response) -> Response {
+    response.insert_header(SERVER, HeaderValue::from_static("pavex"))
+}
+
+pub fn plaintext() -> Response {
+    Response::ok().set_typed_body("Hello, World!")
+}
+
+pub fn json() -> Response {
+    json_response(&Message::HELLO_WORLD)
+}
+
+pub async fn db(pool: &Pool) -> Result<Response, DbError> {
+    let client = pool.get().await?;
+    let world = db::select_world(&client, WorldRng::new().id()).await?;
+    Ok(json_response(&world))
+}
+
+pub async fn queries(head: &RequestHead, pool: &Pool) -> Result<Response, DbError> {
+    let count = queries_param(head);
+    let client = pool.get().await?;
+    let worlds = db::select_random_worlds(&client, &WorldRng::new(), count).await?;
+    Ok(json_response(&worlds))
+}
+
+pub async fn fortunes(pool: &Pool) -> Result<Response, DbError> {
+    let client = pool.get().await?;
+    let mut fortunes = db::select_fortunes(&client).await?;
+    fortune::prepare(&mut fortunes);
+    Ok(Response::ok().set_typed_body(Html::from(fortune::render(&fortunes))))
+}
+
+pub async fn updates(head: &RequestHead, pool: &Pool) -> Result<Response, DbError> {
+    let count = queries_param(head);
+    let rng = WorldRng::new();
+    let client = pool.get().await?;
+    let mut worlds = db::select_random_worlds(&client, &rng, count).await?;
+    for world in &mut worlds {
+        world.random_number = rng.id();
+    }
+    db::update_worlds(&client, &mut worlds).await?;
+    Ok(json_response(&worlds))
+}
+
+pub fn cached_queries(head: &RequestHead, cache: &WorldCache) -> Response {
+    let count = queries_param(head);
+    let worlds: Option<Vec<_>> = WorldRng::new().ids(count).map(|id| cache.get(id)).collect();
+    match worlds {
+        Some(worlds) => json_response(&worlds),
+        // Every id is in the table, so a miss means the cache is broken.
+        None => Response::internal_server_error(),
+    }
+}
+
+#[inline]
+fn queries_param(head: &RequestHead) -> usize {
+    head.target
+        .query()
+        .map_or(query::MIN_QUERIES, query::queries)
+}
+
+fn json_response<T: Serialize>(value: &T) -> Response {
+    match Json::new(value) {
+        Ok(body) => Response::ok().set_typed_body(body),
+        Err(_) => Response::internal_server_error(),
+    }
+}
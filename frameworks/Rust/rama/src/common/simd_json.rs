@@ -15,15 +15,36 @@ use simd_json;
 #[derive(Debug, Clone, Copy, Default)]
 pub struct Json<T>(pub T);
 
+/// Rejection used for [`Json`], mirroring rama's `JsonRejection`.
+#[derive(Debug)]
 pub enum SimdJsonRejection {
+    /// The `Content-Type` is not JSON.
     Json(JsonRejection),
+    /// The body could not be buffered.
     Bytes(BytesRejection),
-    Simd(String),
+    /// The body is not valid JSON.
+    Syntax(simd_json::Error),
+    /// The body is valid JSON but does not match the target type. The error carries the path
+    /// of the field that failed.
+    Data(serde_path_to_error::Error<simd_json::Error>),
 }
 
 impl IntoResponse for SimdJsonRejection {
     fn into_response(self) -> Response {
-        todo!()
+        match self {
+            SimdJsonRejection::Json(rejection) => rejection.into_response(),
+            SimdJsonRejection::Bytes(rejection) => rejection.into_response(),
+            SimdJsonRejection::Syntax(err) => (
+                StatusCode::BAD_REQUEST,
+                format!("Failed to parse the request body as JSON: {err}"),
+            )
+                .into_response(),
+            SimdJsonRejection::Data(err) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!("Failed to deserialize the JSON body into the target type: {err}"),
+            )
+                .into_response(),
+        }
     }
 }
 
@@ -41,7 +62,13 @@ impl From<BytesRejection> for SimdJsonRejection {
 
 impl From<simd_json::Error> for SimdJsonRejection {
     fn from(err: simd_json::Error) -> Self {
-        SimdJsonRejection::Simd(err.to_string())
+        SimdJsonRejection::Syntax(err)
+    }
+}
+
+impl From<serde_path_to_error::Error<simd_json::Error>> for SimdJsonRejection {
+    fn from(err: serde_path_to_error::Error<simd_json::Error>) -> Self {
+        SimdJsonRejection::Data(err)
     }
 }
 
@@ -106,15 +133,10 @@ where
     /// constructing a `Json<T>`.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, SimdJsonRejection> {
         let body = &mut bytes.to_owned();
-        let deserializer = simd_json::from_slice::<T>(body);
-
-        let value = match deserializer {
-            Ok(v) => v,
-            Err(err) => {
-                let rejection = { SimdJsonRejection::from(err) };
-                return Err(rejection);
-            }
-        };
+        // Building the tape validates the whole document, so any error past this point is a
+        // mismatch between the JSON and `T`.
+        let mut deserializer = simd_json::Deserializer::from_slice(body)?;
+        let value = serde_path_to_error::deserialize(&mut deserializer)?;
 
         Ok(Json(value))
     }
@@ -149,3 +171,91 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use rama::http::Body;
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Payload {
+        inner: Inner,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Inner {
+        id: i32,
+    }
+
+    fn json_request(body: Body) -> Request {
+        Request::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .unwrap()
+    }
+
+    #[test]
+    fn valid_body() {
+        let Json(payload) = Json::<Payload>::from_bytes(br#"{"inner":{"id":1}}"#).unwrap();
+        assert_eq!(payload.inner.id, 1);
+    }
+
+    #[test]
+    fn syntax_error_is_bad_request() {
+        let rejection = Json::<Payload>::from_bytes(br#"{"inner":"#).unwrap_err();
+
+        assert!(matches!(rejection, SimdJsonRejection::Syntax(_)));
+        assert_eq!(rejection.into_response().status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn data_error_is_unprocessable_with_path() {
+        let rejection =
+            Json::<Payload>::from_bytes(br#"{"inner":{"id":"one"}}"#).unwrap_err();
+
+        let SimdJsonRejection::Data(err) = &rejection else {
+            panic!("expected a data error, got {rejection:?}");
+        };
+        assert_eq!(err.path().to_string(), "inner.id");
+        assert_eq!(
+            rejection.into_response().status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_content_type_is_unsupported_media_type() {
+        let req = Request::new(Body::from(r#"{"inner":{"id":1}}"#));
+        let rejection = Json::<Payload>::from_request(req).await.unwrap_err();
+
+        assert!(matches!(rejection, SimdJsonRejection::Json(_)));
+        assert_eq!(
+            rejection.into_response().status(),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+    }
+
+    #[tokio::test]
+    async fn unreadable_body_is_rejected() {
+        let body = Body::from_stream(futures::stream::once(async {
+            Err::<bytes::Bytes, _>(io::Error::other("connection reset"))
+        }));
+        let rejection = Json::<Payload>::from_request(json_request(body))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(rejection, SimdJsonRejection::Bytes(_)));
+        assert!(rejection.into_response().status().is_client_error());
+    }
+
+    #[tokio::test]
+    async fn extracts_json_request() {
+        let req = json_request(Body::from(r#"{"inner":{"id":1}}"#));
+        let Json(payload) = Json::<Payload>::from_request(req).await.unwrap();
+        assert_eq!(payload.inner.id, 1);
+    }
+}
@@ -1,19 +1,11 @@
 use rama::http::StatusCode;
-use serde::Deserialize;
 
-#[derive(Debug, Deserialize)]
-pub struct Params {
-    q: Option<String>,
-}
+pub use tfb_common::query::Params;
 
 #[allow(dead_code)]
 #[inline(always)]
 pub fn parse_params(params: Params) -> usize {
-    params
-        .q
-        .and_then(|q| q.parse().ok())
-        .unwrap_or(1)
-        .clamp(1, 500)
+    params.count()
 }
 
 /// Utility function for mapping any error into a `500 Internal Server Error`
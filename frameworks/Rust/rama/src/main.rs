@@ -1,7 +1,6 @@
 mod common;
 mod server;
 
-use common::models::Message;
 use dotenv::dotenv;
 use mimalloc::MiMalloc;
 
@@ -16,6 +15,7 @@ use rama::http::{
     StatusCode,
     service::web::{Router, response::IntoResponse},
 };
+use tfb_common::Message;
 
 /// Return a plaintext static string.
 #[inline(always)]
@@ -6,10 +6,6 @@ use std::time::Duration;
 
 #[cfg(feature = "simd-json")]
 use common::simd_json::Json;
-use common::{
-    models::{FortuneInfo, World},
-    random_id,
-};
 use dotenv::dotenv;
 use mimalloc::MiMalloc;
 use mongodb::{
@@ -27,6 +23,7 @@ use rama::http::{
     },
 };
 use rand::{SeedableRng, rng, rngs::SmallRng};
+use tfb_common::{Fortune, World, random_id};
 use yarte::Template;
 
 #[global_allocator]
@@ -43,7 +40,7 @@ use mongo::database::{
 #[derive(Template)]
 #[template(path = "fortunes.html.hbs")]
 pub struct FortunesTemplate<'a> {
-    pub fortunes: &'a Vec<FortuneInfo>,
+    pub fortunes: &'a Vec<Fortune>,
 }
 
 async fn db(DatabaseConnection(db): DatabaseConnection) -> impl IntoResponse {
@@ -97,17 +94,9 @@ async fn updates(
 async fn fortunes(DatabaseConnection(db): DatabaseConnection) -> impl IntoResponse {
     let fortunes = fetch_fortunes(db).await.expect("could not fetch fortunes");
 
-    let fortune_infos: Vec<FortuneInfo> = fortunes
-        .iter()
-        .map(|f| FortuneInfo {
-            id: f.id,
-            message: f.message.clone(),
-        })
-        .collect();
-
     Html(
         FortunesTemplate {
-            fortunes: &fortune_infos,
+            fortunes: &fortunes,
         }
         .call()
         .expect("error rendering template"),
@@ -2,7 +2,7 @@ mod common;
 mod mongo_raw;
 mod server;
 
-use common::{models::World, random_id};
+use tfb_common::{World, random_id};
 use mongo_raw::database::{
     DatabaseConnection, find_world_by_id, find_worlds, update_worlds,
 };
@@ -12,6 +12,7 @@ use rama::http::{
     },
 };
 use rand::rng;
+use tfb_common::random_id;
 use yarte::Template;
 
 #[global_allocator]
@@ -25,7 +26,7 @@ use rama::http::service::web::response::Json;
 mod server;
 
 use common::{
-    get_env, random_id,
+    get_env,
     utils::{Params, parse_params},
 };
 use pg::database::{DatabaseConnection, PgConnection};
@@ -6,7 +6,7 @@ use common::simd_json::Json;
 #[cfg(not(feature = "simd-json"))]
 use rama::http::service::web::response::Json;
 
-use common::{SELECT_ALL_FORTUNES, SELECT_WORLD_BY_ID, UPDATE_WORLDS, random_ids};
+use common::{SELECT_ALL_FORTUNES, SELECT_WORLD_BY_ID, UPDATE_WORLDS};
 use dotenv::dotenv;
 use futures_util::{TryStreamExt, stream::FuturesUnordered};
 use mimalloc::MiMalloc;
@@ -19,6 +19,7 @@ use rama::http::{
     },
 };
 use rand::{SeedableRng, rng, rngs::SmallRng};
+use tfb_common::{fortune, random_id, random_ids};
 use yarte::Template;
 
 #[global_allocator]
@@ -27,7 +28,7 @@ static GLOBAL: MiMalloc = MiMalloc;
 mod server;
 
 use common::{
-    get_env, random_id,
+    get_env,
     utils::{Params, parse_params},
 };
 use pg_pool::database::{
@@ -79,12 +80,14 @@ async fn fortunes(DatabaseClient(client): DatabaseClient) -> impl IntoResponse {
         .await
         .expect("could not fetch fortunes");
 
-    fortunes.push(Fortune {
-        id: 0,
-        message: "Additional fortune added at request time.".to_string(),
-    });
-
-    fortunes.sort_by(|a, b| a.message.cmp(&b.message));
+    fortune::prepare_with(
+        &mut fortunes,
+        |id, message| Fortune {
+            id,
+            message: message.to_string(),
+        },
+        |fortune| &fortune.message,
+    );
 
     Html(
         FortunesTemplate {
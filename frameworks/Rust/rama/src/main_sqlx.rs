@@ -20,6 +20,7 @@ use rama::{
 };
 use rand::{SeedableRng, rng, rngs::SmallRng};
 use sqlx::models::World;
+use tfb_common::{fortune, random_id, random_ids};
 use yarte::Template;
 
 #[global_allocator]
@@ -33,7 +34,7 @@ use rama::http::service::web::response::Json;
 mod server;
 
 use common::{
-    get_env, random_id, random_ids,
+    get_env,
     utils::{Params, parse_params},
 };
 use sqlx::database::create_pool;
@@ -88,12 +89,14 @@ async fn fortunes(ctx: Context<AppState>) -> impl IntoResponse {
         .await
         .expect("error loading Fortunes");
 
-    fortunes.push(Fortune {
-        id: 0,
-        message: "Additional fortune added at request time.".to_string(),
-    });
-
-    fortunes.sort_by(|a, b| a.message.cmp(&b.message));
+    fortune::prepare_with(
+        &mut fortunes,
+        |id, message| Fortune {
+            id,
+            message: message.to_string(),
+        },
+        |fortune| &fortune.message,
+    );
 
     Html(
         FortunesTemplate {
@@ -8,10 +8,7 @@ use rama::{
 };
 use rand::rngs::SmallRng;
 
-use crate::common::{
-    models::{Fortune, World},
-    random_ids,
-};
+use tfb_common::{Fortune, World, random_ids};
 
 pub struct DatabaseConnection(pub Database);
 
@@ -87,12 +84,7 @@ pub async fn fetch_fortunes(db: Database) -> Result<Vec<Fortune>, MongoError> {
         fortunes.push(doc.expect("could not load fortune"));
     }
 
-    fortunes.push(Fortune {
-        id: 0,
-        message: "Additional fortune added at request time.".to_string(),
-    });
-
-    fortunes.sort_by(|a, b| a.message.cmp(&b.message));
+    tfb_common::fortune::prepare(&mut fortunes);
     Ok(fortunes)
 }
 
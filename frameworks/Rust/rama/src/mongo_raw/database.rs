@@ -11,7 +11,7 @@ use rama::{
 };
 use rand::rngs::SmallRng;
 
-use crate::common::{models::World, random_ids};
+use tfb_common::{World, random_ids};
 
 pub struct DatabaseConnection(pub Database);
 
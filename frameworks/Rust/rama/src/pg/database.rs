@@ -9,7 +9,9 @@ use rand::{SeedableRng, rng, rngs::SmallRng};
 use tokio::pin;
 use tokio_postgres::{Client, NoTls, Statement, connect};
 
-use crate::common::{self, random_id, random_ids};
+use tfb_common::{fortune, random_id, random_ids};
+
+use crate::common;
 
 use super::models::{Fortune, World};
 
@@ -116,10 +118,7 @@ impl PgConnection {
     }
 
     pub async fn fetch_all_fortunes(&self) -> Result<Vec<Fortune>, PgError> {
-        let mut fortunes = vec![Fortune {
-            id: 0,
-            message: "Additional fortune added at request time.".parse().unwrap(),
-        }];
+        let mut fortunes = Vec::with_capacity(13);
 
         let rows = self
             .client
@@ -135,7 +134,14 @@ impl PgConnection {
             });
         }
 
-        fortunes.sort_by(|it, next| it.message.cmp(&next.message));
+        fortune::prepare_with(
+            &mut fortunes,
+            |id, message| Fortune {
+                id,
+                message: message.to_string(),
+            },
+            |fortune| &fortune.message,
+        );
         Ok(fortunes)
     }
 }
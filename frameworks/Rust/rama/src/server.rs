@@ -6,6 +6,7 @@ use std::{
 
 use rama::{
     Layer,
+    graceful::Shutdown,
     http::{
         layer::required_header::AddRequiredResponseHeadersLayer, server::HttpServer,
         service::web::Router,
@@ -14,6 +15,7 @@ use rama::{
     rt::Executor,
     tcp::server::TcpListener,
 };
+use tfb_common::shutdown::{self, DRAIN_TIMEOUT};
 
 /// Reuse an existing listener, ensuring that the socket `backlog`
 /// is set to enable a higher number of pending connections.
@@ -36,6 +38,11 @@ fn set_socket_options(addr: SocketAddr) -> io::Result<TcpListener<()>> {
 /// Build a Rama server with consistent configuration, using the high-level API exposed
 /// by rama 0.2. This is intended for convenience and intentionally does not provide much
 /// customisability.
+///
+/// The server stops accepting on `Ctrl+C` or `SIGTERM` and drains the live
+/// connections before returning. Accept errors are left to rama's listener,
+/// which logs them and pauses for a second on anything but a connection error
+/// (`handle_accept_err` in rama-tcp 0.2's `server/listener.rs`).
 #[allow(dead_code)]
 pub async fn serve<State: Clone + Send + Sync + 'static>(
     state: State,
@@ -47,13 +54,25 @@ pub async fn serve<State: Clone + Send + Sync + 'static>(
     let listener = set_socket_options(addr).expect("couldn't bind to address");
     println!("started rama server on port: {port}");
 
+    let shutdown = Shutdown::new(shutdown::signal());
+    let guard = shutdown.guard();
+
     let app = AddRequiredResponseHeadersLayer::default().layer(app);
-    let http_service = HttpServer::auto(Executor::default()).service(app);
+    let http_service =
+        HttpServer::auto(Executor::graceful(guard.clone())).service(app);
+
+    listener
+        .with_state(state)
+        .serve_graceful(guard, http_service)
+        .await;
 
-    listener.with_state(state).serve(http_service).await;
+    if let Err(error) = shutdown.shutdown_with_limit(DRAIN_TIMEOUT).await {
+        eprintln!("timed out draining connections: {error}");
+    }
 }
 
-/// Start a single-threaded tokio runtime on multiple threads.
+/// Start a single-threaded tokio runtime on multiple threads, and wait for all of
+/// them to return once the servers they run have shut down.
 #[allow(dead_code)]
 pub fn start_tokio<Fut>(f: fn() -> Fut)
 where
@@ -64,14 +83,22 @@ where
         .build()
         .unwrap();
 
-    for _ in 1..num_cpus::get() {
-        std::thread::spawn(move || {
-            let rt = tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-                .unwrap();
-            rt.block_on(f());
-        });
-    }
+    let threads: Vec<_> = (1..num_cpus::get())
+        .map(|_| {
+            std::thread::spawn(move || {
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .unwrap();
+                rt.block_on(f());
+            })
+        })
+        .collect();
     rt.block_on(f());
+
+    for thread in threads {
+        if thread.join().is_err() {
+            eprintln!("a server runtime panicked");
+        }
+    }
 }
@@ -1,7 +1,7 @@
 use futures_util::{stream::FuturesUnordered, StreamExt, TryStreamExt};
 use mongodb::{bson::doc, error::Error, Database};
 
-use crate::{Fortune, World};
+use tfb_common::{fortune, Fortune, World};
 
 pub async fn find_world_by_id(db: Database, id: i32) -> Result<World, Error> {
     let world_collection = db.collection::<World>("world");
@@ -29,11 +29,7 @@ pub async fn fetch_fortunes(db: Database) -> Result<Vec<Fortune>, Error> {
     while let Some(doc) = fortune_cursor.next().await {
         fortunes.push(doc.expect("could not load fortune"));
     }
-    fortunes.push(Fortune {
-        id: 0,
-        message: "Additional fortune added at request time.".to_string(),
-    });
-    fortunes.sort_by(|a, b| a.message.cmp(&b.message));
+    fortune::prepare(&mut fortunes);
     Ok(fortunes)
 }
 
@@ -5,7 +5,7 @@ use mongodb::{
     Database,
 };
 
-use crate::World;
+use tfb_common::World;
 
 pub async fn find_world_by_id(db: Database, id: i32) -> Result<World, Error> {
     let world_collection = db.collection::<RawDocumentBuf>("world");
@@ -26,11 +26,11 @@ pub async fn find_world_by_id(db: Database, id: i32) -> Result<World, Error> {
             .as_i32()
             .expect("could not extract world id"),
         random_number: raw
-            .get("id")
-            .expect("expected to parse world id")
-            .expect("could not get world id")
+            .get("randomNumber")
+            .expect("expected to parse world randomNumber")
+            .expect("could not get world randomNumber")
             .as_i32()
-            .expect("could not extract world id"),
+            .expect("could not extract world randomNumber"),
     })
 }
 
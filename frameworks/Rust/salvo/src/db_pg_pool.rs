@@ -1,9 +1,7 @@
 use deadpool_postgres::{Client, Manager, ManagerConfig, RecyclingMethod};
-use tokio_pg_mapper::FromTokioPostgresRow;
+use tfb_common::{Fortune, World};
 use tokio_postgres::{Error, NoTls, Row, Statement};
 
-use crate::{Fortune, World};
-
 pub async fn create_pool(database_url: String, max_pool_size: u32) -> deadpool_postgres::Pool {
     let pg_config: tokio_postgres::Config = database_url.parse().expect("invalid database url");
 
@@ -22,7 +20,7 @@ pub async fn create_pool(database_url: String, max_pool_size: u32) -> deadpool_p
 pub async fn fetch_world_by_id(client: &Client, number: i32, select: &Statement) -> Result<World, Error> {
     let row: Row = client.query_one(select, &[&number]).await.unwrap();
 
-    Ok(World::from_row(row).unwrap())
+    Ok(World::new(row.get(0), row.get(1)))
 }
 
 pub async fn update_world(client: &Client, update: &Statement, random_id: i32, w_id: i32) -> Result<u64, Error> {
@@ -37,14 +35,14 @@ pub async fn fetch_all_fortunes(client: &Client, select: &Statement) -> Result<V
     let mut fortunes: Vec<Fortune> = Vec::with_capacity(rows.capacity());
 
     for row in rows {
-        fortunes.push(Fortune::from_row(row).unwrap());
+        fortunes.push(Fortune::new(row.get(0), row.get::<_, String>(1)));
     }
 
     Ok(fortunes)
 }
 
 pub async fn prepare_fetch_all_fortunes_statement(client: &Client) -> Statement {
-    client.prepare_cached("SELECT * FROM Fortune").await.unwrap()
+    client.prepare_cached("SELECT id, message FROM Fortune").await.unwrap()
 }
 
 pub async fn prepare_fetch_world_by_id_statement(client: &Client) -> Statement {
@@ -60,25 +58,3 @@ pub async fn prepare_update_world_by_id_statement(client: &Client) -> Statement
         .await
         .unwrap()
 }
-
-markup::define! {
-    FortunesTemplate(items: Vec<Fortune>) {
-        {markup::doctype()}
-        html {
-            head {
-                title { "Fortunes" }
-            }
-            body {
-                table {
-                    tr { th { "id" } th { "message" } }
-                    @for item in items {
-                        tr {
-                            td { {item.id} }
-                            td { {markup::raw(v_htmlescape::escape(&item.message).to_string())} }
-                        }
-                    }
-                }
-            }
-        }
-    }
-}
@@ -4,7 +4,7 @@ use sqlx::{
     Arguments, Error, PgPool, Postgres,
 };
 
-use crate::{Fortune, World};
+use tfb_common::{Fortune, World};
 
 pub async fn create_pool(database_url: String, max_pool_size: u32, min_pool_size: u32) -> PgPool {
     PgPoolOptions::new()
@@ -19,17 +19,18 @@ pub async fn fetch_world(mut conn: PoolConnection<Postgres>, number: i32) -> Res
     let mut args = PgArguments::default();
     args.add(number);
 
-    let world: World = sqlx::query_as_with("SELECT id, randomnumber FROM World WHERE id = $1", args)
+    let (id, random_number): (i32, i32) = sqlx::query_as_with("SELECT id, randomnumber FROM World WHERE id = $1", args)
         .fetch_one(&mut *conn)
-        .await
-        .expect("error loading world");
-    Ok(world)
+        .await?;
+    Ok(World::new(id, random_number))
 }
 
 pub async fn fetch_fortunes(mut conn: PoolConnection<Postgres>) -> Result<Vec<Fortune>, Error> {
-    let fortunes: Vec<Fortune> = sqlx::query_as("SELECT * FROM Fortune")
+    let rows: Vec<(i32, String)> = sqlx::query_as("SELECT id, message FROM Fortune")
         .fetch_all(&mut *conn)
-        .await
-        .expect("error loading Fortunes");
-    Ok(fortunes)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, message)| Fortune::new(id, message))
+        .collect())
 }
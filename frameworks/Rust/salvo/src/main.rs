@@ -9,25 +9,16 @@ use salvo::conn::tcp::TcpAcceptor;
 use salvo::http::body::ResBody;
 use salvo::http::header::{self, HeaderValue};
 use salvo::prelude::*;
-use serde::Serialize;
+use tfb_common::Message;
 
 mod utils;
 
-
-#[derive(Serialize)]
-pub struct Message {
-    pub message: &'static str,
-}
-
 #[handler]
 fn json(res: &mut Response) {
     let headers = res.headers_mut();
     headers.insert(header::SERVER, HeaderValue::from_static("salvo"));
     headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
-    let data = serde_json::to_vec(&Message {
-        message: "Hello, World!",
-    })
-    .unwrap();
+    let data = serde_json::to_vec(&Message::HELLO_WORLD).unwrap();
     res.body(ResBody::Once(Bytes::from(data)));
 }
 
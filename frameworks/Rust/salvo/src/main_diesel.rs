@@ -7,8 +7,6 @@
 extern crate diesel;
 
 use bytes::Bytes;
-use std::cmp;
-use std::fmt::Write;
 use std::sync::Arc;
 use std::thread::available_parallelism;
 
@@ -17,17 +15,15 @@ use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool, PoolError, PooledConnection};
 use dotenvy::dotenv;
 use once_cell::sync::OnceCell;
-use rand::rngs::SmallRng;
-use rand::{Rng, SeedableRng};
 use salvo::conn::tcp::TcpAcceptor;
 use salvo::http::header::{self, HeaderValue};
 use salvo::http::ResBody;
 use salvo::prelude::*;
+use tfb_common::fortune::{prepare, render};
+use tfb_common::{query, Fortune, World, WorldRng};
 
-mod models_diesel;
 mod schema;
 mod utils;
-use models_diesel::*;
 use schema::*;
 
 type PgPool = Pool<ConnectionManager<PgConnection>>;
@@ -47,13 +43,19 @@ fn create_pool(database_url: &str, size: u32) -> Result<PgPool, PoolError> {
         .max_lifetime(None)
         .build(manager)
 }
+fn load_world(conn: &mut PgConnection, id: i32) -> QueryResult<World> {
+    world::table
+        .find(id)
+        .select((world::id, world::randomnumber))
+        .first::<(i32, i32)>(conn)
+        .map(|(id, random_number)| World::new(id, random_number))
+}
 
 #[handler]
 async fn world_row(res: &mut Response) -> Result<(), Error> {
-    let mut rng = SmallRng::from_entropy();
-    let random_id = rng.gen_range(1..10_001);
+    let random_id = WorldRng::new().id();
     let mut conn = connect()?;
-    let world = world::table.find(random_id).first::<World>(&mut conn)?;
+    let world = load_world(&mut conn, random_id)?;
     drop(conn);
 
     let data = serde_json::to_vec(&world).unwrap();
@@ -66,15 +68,11 @@ async fn world_row(res: &mut Response) -> Result<(), Error> {
 
 #[handler]
 async fn queries(req: &mut Request, res: &mut Response) -> Result<(), Error> {
-    let count = req.query::<u16>("q").unwrap_or(1);
-    let count = cmp::min(500, cmp::max(1, count));
-    let mut worlds = Vec::with_capacity(count as usize);
-    let mut rng = SmallRng::from_entropy();
+    let count = query::queries(req.uri().query().unwrap_or_default());
+    let mut worlds = Vec::with_capacity(count);
     let mut conn = connect()?;
-    for _ in 0..count {
-        let id = rng.gen_range(1..10_001);
-        let w = world::table.find(id).get_result::<World>(&mut conn)?;
-        worlds.push(w);
+    for id in WorldRng::new().ids(count) {
+        worlds.push(load_world(&mut conn, id)?);
     }
     drop(conn);
 
@@ -88,15 +86,13 @@ async fn queries(req: &mut Request, res: &mut Response) -> Result<(), Error> {
 
 #[handler]
 async fn updates(req: &mut Request, res: &mut Response) -> Result<(), Error> {
-    let count = req.query::<u16>("q").unwrap_or(1);
-    let count = cmp::min(500, cmp::max(1, count));
+    let count = query::queries(req.uri().query().unwrap_or_default());
     let mut conn = connect()?;
-    let mut worlds = Vec::with_capacity(count as usize);
-    let mut rng = SmallRng::from_entropy();
-    for _ in 0..count {
-        let w_id: i32 = rng.gen_range(1..10_001);
-        let mut w = world::table.find(w_id).first::<World>(&mut conn)?;
-        w.randomnumber = rng.gen_range(1..10_001);
+    let mut worlds = Vec::with_capacity(count);
+    let rng = WorldRng::new();
+    for w_id in rng.ids(count) {
+        let mut w = load_world(&mut conn, w_id)?;
+        w.random_number = rng.id();
         worlds.push(w);
     }
     worlds.sort_by_key(|w| w.id);
@@ -104,7 +100,7 @@ async fn updates(req: &mut Request, res: &mut Response) -> Result<(), Error> {
         for w in &worlds {
             diesel::update(world::table)
                 .filter(world::id.eq(w.id))
-                .set(world::randomnumber.eq(w.randomnumber))
+                .set(world::randomnumber.eq(w.random_number))
                 .execute(conn)?;
         }
         Ok(())
@@ -122,16 +118,15 @@ async fn updates(req: &mut Request, res: &mut Response) -> Result<(), Error> {
 #[handler]
 async fn fortunes(res: &mut Response) -> Result<(), Error> {
     let mut conn = connect()?;
-    let mut items = fortune::table.get_results::<Fortune>(&mut conn)?;
+    let mut items = fortune::table
+        .select((fortune::id, fortune::message))
+        .load::<(i32, String)>(&mut conn)?
+        .into_iter()
+        .map(|(id, message)| Fortune::new(id, message))
+        .collect::<Vec<_>>();
     drop(conn);
-    items.push(Fortune {
-        id: 0,
-        message: "Additional fortune added at request time.".to_string(),
-    });
-
-    items.sort_by(|it, next| it.message.cmp(&next.message));
-    let mut data = String::new();
-    write!(&mut data, "{}", FortunesTemplate { items }).unwrap();
+    prepare(&mut items);
+    let data = render(&items);
 
     let headers = res.headers_mut();
     headers.insert(header::SERVER, HeaderValue::from_static("salvo"));
@@ -143,28 +138,6 @@ async fn fortunes(res: &mut Response) -> Result<(), Error> {
     Ok(())
 }
 
-markup::define! {
-    FortunesTemplate(items: Vec<Fortune>) {
-        {markup::doctype()}
-        html {
-            head {
-                title { "Fortunes" }
-            }
-            body {
-                table {
-                    tr { th { "id" } th { "message" } }
-                    @for item in items {
-                        tr {
-                            td { {item.id} }
-                            td { {markup::raw(v_htmlescape::escape(&item.message).to_string())} }
-                        }
-                    }
-                }
-            }
-        }
-    }
-}
-
 fn main() {
     dotenv().ok();
 
@@ -3,7 +3,6 @@
 // #[global_allocator]
 // static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
-use std::cmp;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 use std::thread::available_parallelism;
@@ -13,29 +12,24 @@ use bytes::Bytes;
 use dotenvy::dotenv;
 use lru::LruCache;
 use once_cell::sync::OnceCell;
-use rand::rngs::SmallRng;
-use rand::{Rng, SeedableRng};
 use salvo::conn::tcp::TcpAcceptor;
 use salvo::http::header::{self, HeaderValue};
 use salvo::http::ResBody;
 use salvo::prelude::*;
+use tfb_common::{query, World, WorldRng};
 
-mod models_pg;
-mod utils;
-use models_pg::*;
 mod db_pg;
+mod utils;
 use db_pg::PgConnection;
 
 static CACHED_WORLDS: OnceCell<LruCache<usize, World>> = OnceCell::new();
 
 #[handler]
 fn cached_queries(req: &mut Request, res: &mut Response) -> Result<(), Error> {
-    let count = req.query::<u16>("q").unwrap_or(1);
-    let count = cmp::min(500, cmp::max(1, count));
-    let mut worlds = Vec::with_capacity(count as usize);
-    let mut rng = SmallRng::from_entropy();
-    for _ in 0..count {
-        let idx = rng.gen_range(0..10_000);
+    let count = query::queries(req.uri().query().unwrap_or_default());
+    let mut worlds = Vec::with_capacity(count);
+    for id in WorldRng::new().ids(count) {
+        let idx = id as usize - 1;
         unsafe {
             let w = CACHED_WORLDS.get_unchecked().peek(&idx).cloned().unwrap();
             worlds.push(w);
@@ -3,8 +3,6 @@
 // #[global_allocator]
 // static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
-use std::cmp;
-use std::fmt::Write;
 use std::thread::available_parallelism;
 use std::time::Duration;
 
@@ -15,24 +13,20 @@ use mongodb::{
     options::{ClientOptions, Compressor},
     Client, Database,
 };
-use rand::rngs::SmallRng;
-use rand::{Rng, SeedableRng};
 use salvo::conn::tcp::TcpAcceptor;
 use salvo::http::header::{self, HeaderValue};
 use salvo::http::ResBody;
 use salvo::prelude::*;
+use tfb_common::{query, WorldRng};
 
 mod db_mongo;
-mod models_mongo;
 mod utils;
 
 use db_mongo::*;
-use models_mongo::*;
 
 #[handler]
 async fn world_row(res: &mut Response, depot: &mut Depot) -> Result<(), Error> {
-    let mut rng = SmallRng::from_entropy();
-    let random_id = rng.gen_range(1..10_001);
+    let random_id = WorldRng::new().id();
 
     let db = depot.obtain::<Database>().unwrap();
     let world = find_world_by_id(db.clone(), random_id).await?;
@@ -47,14 +41,9 @@ async fn world_row(res: &mut Response, depot: &mut Depot) -> Result<(), Error> {
 
 #[handler]
 async fn queries(req: &mut Request, depot: &mut Depot, res: &mut Response) -> Result<(), Error> {
-    let count = req.query::<u16>("q").unwrap_or(1);
-    let count = cmp::min(500, cmp::max(1, count));
+    let count = query::queries(req.uri().query().unwrap_or_default());
 
-    let mut rng = SmallRng::from_entropy();
-    let mut ids: Vec<i32> = Vec::with_capacity(count as usize);
-    for _ in 0..count {
-        ids.push(rng.gen_range(1..10_001));
-    }
+    let ids: Vec<i32> = WorldRng::new().ids(count).collect();
     let db = depot.obtain::<Database>().unwrap();
     let worlds = find_worlds(db.clone(), ids).await?;
 
@@ -68,19 +57,15 @@ async fn queries(req: &mut Request, depot: &mut Depot, res: &mut Response) -> Re
 
 #[handler]
 async fn updates(req: &mut Request, depot: &mut Depot, res: &mut Response) -> Result<(), Error> {
-    let count = req.query::<u16>("q").unwrap_or(1);
-    let count = cmp::min(500, cmp::max(1, count));
+    let count = query::queries(req.uri().query().unwrap_or_default());
 
-    let mut rng = SmallRng::from_entropy();
-    let mut ids: Vec<i32> = Vec::with_capacity(count as usize);
-    for _ in 0..count {
-        ids.push(rng.gen_range(1..10_001));
-    }
+    let rng = WorldRng::new();
+    let ids: Vec<i32> = rng.ids(count).collect();
 
     let db = depot.obtain::<Database>().unwrap();
     let mut worlds = find_worlds(db.clone(), ids).await?;
     for world in &mut worlds {
-        world.random_number = rng.gen_range(1..10_001);
+        world.random_number = rng.id();
     }
     let data = serde_json::to_vec(&worlds)?;
     update_worlds(db.clone(), worlds).await?;
@@ -96,9 +81,7 @@ async fn updates(req: &mut Request, depot: &mut Depot, res: &mut Response) -> Re
 async fn fortunes(res: &mut Response, depot: &mut Depot) -> Result<(), Error> {
     let db = depot.obtain::<Database>().unwrap();
     let items = fetch_fortunes(db.clone()).await?;
-
-    let mut data = String::new();
-    write!(&mut data, "{}", FortunesTemplate { items })?;
+    let data = tfb_common::fortune::render(&items);
 
     let headers = res.headers_mut();
     headers.insert(header::SERVER, HeaderValue::from_static("salvo"));
@@ -110,28 +93,6 @@ async fn fortunes(res: &mut Response, depot: &mut Depot) -> Result<(), Error> {
     Ok(())
 }
 
-markup::define! {
-    FortunesTemplate(items: Vec<Fortune>) {
-        {markup::doctype()}
-        html {
-            head {
-                title { "Fortunes" }
-            }
-            body {
-                table {
-                    tr { th { "id" } th { "message" } }
-                    @for item in items {
-                        tr {
-                            td { {item.id} }
-                            td { {markup::raw(v_htmlescape::escape(&item.message).to_string())} }
-                        }
-                    }
-                }
-            }
-        }
-    }
-}
-
 fn main() {
     dotenv().ok();
 
@@ -3,8 +3,6 @@
 // #[global_allocator]
 // static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
-use std::cmp;
-use std::fmt::Write;
 use std::thread::available_parallelism;
 
 use async_trait::async_trait;
@@ -15,9 +13,9 @@ use salvo::http::header::{self, HeaderValue};
 use salvo::http::ResBody;
 use salvo::prelude::*;
 use salvo::routing::FlowCtrl;
+use tfb_common::query;
 
 mod db_pg;
-mod models_pg;
 mod utils;
 use db_pg::PgConnection;
 
@@ -61,8 +59,7 @@ impl WorldsHandler {
 #[async_trait]
 impl Handler for WorldsHandler {
     async fn handle(&self, req: &mut Request, _depot: &mut Depot, res: &mut Response, _ctrl: &mut FlowCtrl) {
-        let count = req.query::<u16>("q").unwrap_or(1);
-        let count = cmp::min(500, cmp::max(1, count));
+        let count = query::queries(req.uri().query().unwrap_or_default());
         let worlds = self.conn.get_worlds(count).await.unwrap();
 
         let data = serde_json::to_vec(&worlds).unwrap();
@@ -88,8 +85,7 @@ impl UpdatesHandler {
 #[async_trait]
 impl Handler for UpdatesHandler {
     async fn handle(&self, req: &mut Request, _depot: &mut Depot, res: &mut Response, _ctrl: &mut FlowCtrl) {
-        let count = req.query::<u16>("q").unwrap_or(1);
-        let count = cmp::min(500, cmp::max(1, count));
+        let count = query::queries(req.uri().query().unwrap_or_default());
         let worlds = self.conn.update(count).await.unwrap();
 
         let data = serde_json::to_vec(&worlds).unwrap();
@@ -115,8 +111,7 @@ impl FortunesHandler {
 #[async_trait]
 impl Handler for FortunesHandler {
     async fn handle(&self, _req: &mut Request, _depot: &mut Depot, res: &mut Response, _ctrl: &mut FlowCtrl) {
-        let mut data = String::new();
-        write!(&mut data, "{}", self.conn.tell_fortune().await.unwrap()).unwrap();
+        let data = self.conn.tell_fortune().await.unwrap();
 
         let headers = res.headers_mut();
         headers.insert(header::SERVER, HeaderValue::from_static("salvo"));
@@ -3,8 +3,6 @@
 // #[global_allocator]
 // static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
-use std::cmp;
-use std::fmt::Write;
 use std::sync::Arc;
 use std::thread::available_parallelism;
 
@@ -14,19 +12,16 @@ use deadpool_postgres::Pool;
 use dotenvy::dotenv;
 use futures_util::{stream::FuturesUnordered, TryStreamExt};
 use once_cell::sync::OnceCell;
-use rand::rngs::SmallRng;
-use rand::{Rng, SeedableRng};
 use salvo::conn::tcp::TcpAcceptor;
 use salvo::http::header::{self, HeaderValue};
 use salvo::http::ResBody;
 use salvo::prelude::*;
+use tfb_common::{fortune, query, World, WorldRng};
 
 mod db_pg_pool;
-mod models_pg_pool;
 mod utils;
 
 use db_pg_pool::*;
-use models_pg_pool::*;
 
 static DB_POOL: OnceCell<Pool> = OnceCell::new();
 
@@ -35,8 +30,7 @@ fn pool() -> &'static Pool {
 }
 #[handler]
 async fn world_row(res: &mut Response) -> Result<(), Error> {
-    let mut rng = SmallRng::from_entropy();
-    let random_id = rng.gen_range(1..10_001);
+    let random_id = WorldRng::new().id();
 
     let client = pool().get().await?;
     let select = prepare_fetch_world_by_id_statement(&client).await;
@@ -53,16 +47,13 @@ async fn world_row(res: &mut Response) -> Result<(), Error> {
 
 #[handler]
 async fn queries(req: &mut Request, res: &mut Response) -> Result<(), Error> {
-    let count = req.query::<u16>("q").unwrap_or(1);
-    let count = cmp::min(500, cmp::max(1, count));
+    let count = query::queries(req.uri().query().unwrap_or_default());
 
-    let mut rng = SmallRng::from_entropy();
     let client = pool().get().await?;
     let select = prepare_fetch_world_by_id_statement(&client).await;
     let future_worlds = FuturesUnordered::new();
 
-    for _ in 0..count {
-        let w_id = (rng.gen::<u32>() % 10_000 + 1) as i32;
+    for w_id in WorldRng::new().ids(count) {
         future_worlds.push(fetch_world_by_id(&client, w_id, &select));
     }
     let worlds: Vec<World> = future_worlds.try_collect().await?;
@@ -78,28 +69,25 @@ async fn queries(req: &mut Request, res: &mut Response) -> Result<(), Error> {
 
 #[handler]
 async fn updates(req: &mut Request, res: &mut Response) -> Result<(), Error> {
-    let count = req.query::<u16>("q").unwrap_or(1);
-    let count = cmp::min(500, cmp::max(1, count));
+    let count = query::queries(req.uri().query().unwrap_or_default());
 
-    let mut rng = SmallRng::from_entropy();
+    let rng = WorldRng::new();
     let client = pool().get().await?;
     let select = prepare_fetch_world_by_id_statement(&client).await;
 
     let future_worlds = FuturesUnordered::new();
 
-    for _ in 0..count {
-        let query_id = rng.gen_range(1..10_001);
+    for query_id in rng.ids(count) {
         future_worlds.push(fetch_world_by_id(&client, query_id, &select));
     }
 
-    let worlds: Vec<World> = future_worlds.try_collect().await?;
+    let mut worlds: Vec<World> = future_worlds.try_collect().await?;
     let update = prepare_update_world_by_id_statement(&client).await;
 
     let future_world_updates = FuturesUnordered::new();
-    for w in &worlds {
-        let random_id = rng.gen_range(1..10_001);
-        let w_id = w.id;
-        future_world_updates.push(update_world(&client, &update, random_id, w_id));
+    for w in &mut worlds {
+        w.random_number = rng.id();
+        future_world_updates.push(update_world(&client, &update, w.random_number, w.id));
     }
     let _world_updates: Vec<u64> = future_world_updates.try_collect().await?;
     drop(client);
@@ -118,14 +106,8 @@ async fn fortunes(res: &mut Response) -> Result<(), Error> {
     let select = prepare_fetch_all_fortunes_statement(&client).await;
     let mut items = fetch_all_fortunes(&client, &select).await?;
     drop(client);
-    items.push(Fortune {
-        id: 0,
-        message: "Additional fortune added at request time.".to_string(),
-    });
-    items.sort_by(|a, b| a.message.cmp(&b.message));
-
-    let mut data = String::new();
-    write!(&mut data, "{}", FortunesTemplate { items }).unwrap();
+    fortune::prepare(&mut items);
+    let data = fortune::render(&items);
 
     let headers = res.headers_mut();
     headers.insert(header::SERVER, HeaderValue::from_static("salvo"));
@@ -137,28 +119,6 @@ async fn fortunes(res: &mut Response) -> Result<(), Error> {
     Ok(())
 }
 
-markup::define! {
-    FortunesTemplate(items: Vec<Fortune>) {
-        {markup::doctype()}
-        html {
-            head {
-                title { "Fortunes" }
-            }
-            body {
-                table {
-                    tr { th { "id" } th { "message" } }
-                    @for item in items {
-                        tr {
-                            td { {item.id} }
-                            td { {markup::raw(v_htmlescape::escape(&item.message).to_string())} }
-                        }
-                    }
-                }
-            }
-        }
-    }
-}
-
 fn main() {
     dotenv().ok();
 
@@ -3,7 +3,6 @@
 // #[global_allocator]
 // static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
-use std::fmt::Write;
 use std::sync::Arc;
 use std::thread::available_parallelism;
 
@@ -11,20 +10,17 @@ use anyhow::Error;
 use bytes::Bytes;
 use dotenvy::dotenv;
 use once_cell::sync::OnceCell;
-use rand::rngs::SmallRng;
-use rand::{Rng, SeedableRng};
 use salvo::conn::tcp::TcpAcceptor;
 use salvo::http::header::{self, HeaderValue};
 use salvo::http::ResBody;
 use salvo::prelude::*;
 use sqlx::PgPool;
+use tfb_common::{fortune, WorldRng};
 
 mod db_sqlx;
-mod models_sqlx;
 mod utils;
 
 use db_sqlx::*;
-use models_sqlx::*;
 
 static DB_POOL: OnceCell<PgPool> = OnceCell::new();
 
@@ -33,8 +29,7 @@ fn pool() -> &'static PgPool {
 }
 #[handler]
 async fn world_row(res: &mut Response) -> Result<(), Error> {
-    let mut rng = SmallRng::from_entropy();
-    let random_id = rng.gen_range(1..10_001);
+    let random_id = WorldRng::new().id();
 
     let conn = pool().acquire().await?;
     let world = fetch_world(conn, random_id).await?;
@@ -51,14 +46,8 @@ async fn world_row(res: &mut Response) -> Result<(), Error> {
 async fn fortunes(res: &mut Response) -> Result<(), Error> {
     let conn = pool().acquire().await?;
     let mut items = fetch_fortunes(conn).await?;
-    items.push(Fortune {
-        id: 0,
-        message: "Additional fortune added at request time.".to_string(),
-    });
-    items.sort_by(|a, b| a.message.cmp(&b.message));
-
-    let mut data = String::new();
-    write!(&mut data, "{}", FortunesTemplate { items }).unwrap();
+    fortune::prepare(&mut items);
+    let data = fortune::render(&items);
 
     let headers = res.headers_mut();
     headers.insert(header::SERVER, HeaderValue::from_static("salvo"));
@@ -70,28 +59,6 @@ async fn fortunes(res: &mut Response) -> Result<(), Error> {
     Ok(())
 }
 
-markup::define! {
-    FortunesTemplate(items: Vec<Fortune>) {
-        {markup::doctype()}
-        html {
-            head {
-                title { "Fortunes" }
-            }
-            body {
-                table {
-                    tr { th { "id" } th { "message" } }
-                    @for item in items {
-                        tr {
-                            td { {item.id} }
-                            td { {markup::raw(v_htmlescape::escape(&item.message).to_string())} }
-                        }
-                    }
-                }
-            }
-        }
-    }
-}
-
 fn main() {
     dotenv().ok();
 
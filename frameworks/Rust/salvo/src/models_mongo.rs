@@ -1,14 +0,0 @@
-use serde::{Deserialize, Serialize};
-
-#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
-pub struct Fortune {
-    pub id: i32,
-    pub message: String,
-}
-
-#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
-pub struct World {
-    pub id: i32,
-    #[serde(rename = "randomNumber")]
-    pub random_number: i32,
-}
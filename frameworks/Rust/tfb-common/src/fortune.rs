@@ -0,0 +1,250 @@
+//! The fortunes test: the fortune added at request time, the sort rule and
+//! the HTML template.
+
+use std::borrow::Cow;
+
+use crate::models::Fortune;
+
+/// Message of the fortune added at request time.
+pub const ADDITIONAL_FORTUNE: &str = "Additional fortune added at request time.";
+
+const HEAD: &str = "<!DOCTYPE html><html><head><title>Fortunes</title></head><body>\
+    <table><tr><th>id</th><th>message</th></tr>";
+const TAIL: &str = "</table></body></html>";
+
+/// Add the request-time fortune and sort all fortunes by message.
+#[inline]
+pub fn prepare(fortunes: &mut Vec<Fortune>) {
+    prepare_with(
+        fortunes,
+        |id, message| Fortune {
+            id,
+            message: Cow::Borrowed(message),
+        },
+        |fortune| &fortune.message,
+    );
+}
+
+/// [`prepare`] for an implementation's own fortune type, such as one borrowing
+/// its message from the database row: `new` builds the request-time fortune
+/// from its id and message, and `message` returns the message to sort by.
+#[inline]
+pub fn prepare_with<T>(
+    fortunes: &mut Vec<T>,
+    new: impl FnOnce(i32, &'static str) -> T,
+    message: impl Fn(&T) -> &str,
+) {
+    fortunes.push(new(0, ADDITIONAL_FORTUNE));
+    fortunes.sort_unstable_by(|a, b| message(a).cmp(message(b)));
+}
+
+/// Render the fortunes table.
+pub fn render(fortunes: &[Fortune]) -> String {
+    let mut html = String::with_capacity(capacity(fortunes));
+    render_into(fortunes, &mut html);
+    html
+}
+
+/// Render the fortunes table at the end of `html`.
+pub fn render_into(fortunes: &[Fortune], html: &mut String) {
+    html.reserve(capacity(fortunes));
+    html.push_str(HEAD);
+    for fortune in fortunes {
+        html.push_str("<tr><td>");
+        html.push_str(itoa(fortune.id, &mut [0; 11]));
+        html.push_str("</td><td>");
+        escape_into(&fortune.message, html);
+        html.push_str("</td></tr>");
+    }
+    html.push_str(TAIL);
+}
+
+/// Append `text` to `html` with the HTML special characters escaped.
+pub fn escape_into(text: &str, html: &mut String) {
+    let mut last = 0;
+    for (i, byte) in text.bytes().enumerate() {
+        let escaped = match byte {
+            b'&' => "&amp;",
+            b'<' => "&lt;",
+            b'>' => "&gt;",
+            b'"' => "&quot;",
+            b'\'' => "&#x27;",
+            _ => continue,
+        };
+        html.push_str(&text[last..i]);
+        html.push_str(escaped);
+        last = i + 1;
+    }
+    html.push_str(&text[last..]);
+}
+
+/// Rough size of the rendered page, to render without reallocating.
+#[inline(always)]
+fn capacity(fortunes: &[Fortune]) -> usize {
+    HEAD.len()
+        + TAIL.len()
+        + fortunes
+            .iter()
+            .map(|fortune| 40 + fortune.message.len())
+            .sum::<usize>()
+}
+
+/// Format an `i32` into `buf` without going through `core::fmt`.
+#[inline(always)]
+fn itoa(n: i32, buf: &mut [u8; 11]) -> &str {
+    let mut value = n.unsigned_abs();
+    let mut pos = buf.len();
+    loop {
+        pos -= 1;
+        buf[pos] = b'0' + (value % 10) as u8;
+        value /= 10;
+        if value == 0 {
+            break;
+        }
+    }
+    if n < 0 {
+        pos -= 1;
+        buf[pos] = b'-';
+    }
+    // only ASCII digits and '-' were written
+    std::str::from_utf8(&buf[pos..]).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The `Fortune` table as seeded by `toolset/databases`.
+    fn seeded() -> Vec<Fortune> {
+        [
+            (1, "fortune: No such file or directory"),
+            (2, "A computer scientist is someone who fixes things that aren't broken."),
+            (3, "After enough decimal places, nobody gives a damn."),
+            (4, "A bad random number generator: 1, 1, 1, 1, 1, 4.33e+67, 1, 1, 1"),
+            (5, "A computer program does what you tell it to do, not what you want it to do."),
+            (6, "Emacs is a nice operating system, but I prefer UNIX. — Tom Christaensen"),
+            (7, "Any program that runs right is obsolete."),
+            (8, "A list is only as strong as its weakest link. — Donald Knuth"),
+            (9, "Feature: A bug with seniority."),
+            (10, "Computers make very fast, very accurate mistakes."),
+            (11, "<script>alert(\"This should not be displayed in a browser alert box.\");</script>"),
+            (12, "フレームワークのベンチマーク"),
+        ]
+        .into_iter()
+        .map(|(id, message)| Fortune::new(id, message))
+        .collect()
+    }
+
+    /// `valid_fortune` from `toolset/test_types/fortune/fortune_html_parser.py`,
+    /// without its line breaks. The parser also accepts `&#x27;` for its `&apos;`.
+    const REFERENCE: &str = "<!DOCTYPE html><html><head><title>Fortunes</title></head><body>\
+        <table><tr><th>id</th><th>message</th></tr>\
+        <tr><td>11</td><td>&lt;script&gt;alert(&quot;This should not be displayed in a browser alert box.&quot;);&lt;/script&gt;</td></tr>\
+        <tr><td>4</td><td>A bad random number generator: 1, 1, 1, 1, 1, 4.33e+67, 1, 1, 1</td></tr>\
+        <tr><td>5</td><td>A computer program does what you tell it to do, not what you want it to do.</td></tr>\
+        <tr><td>2</td><td>A computer scientist is someone who fixes things that aren&#x27;t broken.</td></tr>\
+        <tr><td>8</td><td>A list is only as strong as its weakest link. — Donald Knuth</td></tr>\
+        <tr><td>0</td><td>Additional fortune added at request time.</td></tr>\
+        <tr><td>3</td><td>After enough decimal places, nobody gives a damn.</td></tr>\
+        <tr><td>7</td><td>Any program that runs right is obsolete.</td></tr>\
+        <tr><td>10</td><td>Computers make very fast, very accurate mistakes.</td></tr>\
+        <tr><td>6</td><td>Emacs is a nice operating system, but I prefer UNIX. — Tom Christaensen</td></tr>\
+        <tr><td>9</td><td>Feature: A bug with seniority.</td></tr>\
+        <tr><td>1</td><td>fortune: No such file or directory</td></tr>\
+        <tr><td>12</td><td>フレームワークのベンチマーク</td></tr>\
+        </table></body></html>";
+
+    fn escape(text: &str) -> String {
+        let mut html = String::new();
+        escape_into(text, &mut html);
+        html
+    }
+
+    #[test]
+    fn escapes_special_characters() {
+        let cases = [
+            ("<", "&lt;"),
+            (">", "&gt;"),
+            ("&", "&amp;"),
+            ("\"", "&quot;"),
+            ("'", "&#x27;"),
+            ("", ""),
+            ("plain — ベンチマーク", "plain — ベンチマーク"),
+            ("a<b>c", "a&lt;b&gt;c"),
+            ("&&", "&amp;&amp;"),
+            ("&amp;", "&amp;amp;"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(escape(text), expected, "escaping {text:?}");
+        }
+    }
+
+    #[test]
+    fn escape_appends() {
+        let mut html = String::from("<td>");
+        escape_into("'x'", &mut html);
+        assert_eq!(html, "<td>&#x27;x&#x27;");
+    }
+
+    #[test]
+    fn prepare_inserts_then_sorts() {
+        let mut fortunes = vec![
+            Fortune::new(2, "b"),
+            Fortune::new(1, "a"),
+            Fortune::new(3, "C"),
+        ];
+        prepare(&mut fortunes);
+
+        let order: Vec<_> = fortunes.iter().map(|f| (f.id, &*f.message)).collect();
+        // sorted by bytes: uppercase before lowercase
+        assert_eq!(
+            order,
+            [(0, ADDITIONAL_FORTUNE), (3, "C"), (1, "a"), (2, "b")]
+        );
+    }
+
+    #[test]
+    fn prepare_on_empty_table() {
+        let mut fortunes = Vec::new();
+        prepare(&mut fortunes);
+        assert_eq!(fortunes, [Fortune::new(0, ADDITIONAL_FORTUNE)]);
+    }
+
+    #[test]
+    fn prepare_with_borrowed_messages() {
+        let rows = [(2, String::from("b")), (1, String::from("a"))];
+        let mut fortunes: Vec<(i32, &str)> =
+            rows.iter().map(|(id, m)| (*id, &**m)).collect();
+        prepare_with(&mut fortunes, |id, message| (id, message), |f| f.1);
+        assert_eq!(fortunes, [(0, ADDITIONAL_FORTUNE), (1, "a"), (2, "b")]);
+    }
+
+    #[test]
+    fn itoa_bounds() {
+        let cases = [
+            (i32::MIN, "-2147483648"),
+            (-1, "-1"),
+            (0, "0"),
+            (7, "7"),
+            (10_000, "10000"),
+            (i32::MAX, "2147483647"),
+        ];
+        for (n, expected) in cases {
+            assert_eq!(itoa(n, &mut [0; 11]), expected);
+        }
+    }
+
+    #[test]
+    fn render_matches_reference() {
+        let mut fortunes = seeded();
+        prepare(&mut fortunes);
+        assert_eq!(render(&fortunes), REFERENCE);
+    }
+
+    #[test]
+    fn render_into_appends() {
+        let mut html = String::from("prefix");
+        render_into(&[], &mut html);
+        assert_eq!(html, format!("prefix{HEAD}{TAIL}"));
+    }
+}
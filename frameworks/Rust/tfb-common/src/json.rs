@@ -0,0 +1,83 @@
+//! JSON serialization through the backend selected by the crate features.
+//!
+//! `simd-json` takes precedence over `sonic-rs`, which takes precedence over
+//! `serde_json`, so enabling one of the faster backends swaps it in without
+//! touching the callers.
+
+use std::io;
+
+use serde::Serialize;
+
+/// Serialize `value` into a new buffer.
+#[inline(always)]
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> io::Result<Vec<u8>> {
+    #[cfg(feature = "simd-json")]
+    {
+        simd_json::to_vec(value).map_err(io::Error::other)
+    }
+
+    #[cfg(all(feature = "sonic-rs", not(feature = "simd-json")))]
+    {
+        sonic_rs::to_vec(value).map_err(io::Error::other)
+    }
+
+    #[cfg(not(any(feature = "sonic-rs", feature = "simd-json")))]
+    {
+        serde_json::to_vec(value).map_err(io::Error::other)
+    }
+}
+
+/// Serialize `value` into `writer`.
+///
+/// `sonic-rs` only writes into its own buffer types, so it falls back to
+/// `serde_json` here.
+#[inline(always)]
+pub fn to_writer<W: io::Write, T: Serialize + ?Sized>(
+    writer: W,
+    value: &T,
+) -> io::Result<()> {
+    #[cfg(feature = "simd-json")]
+    {
+        simd_json::to_writer(writer, value).map_err(io::Error::other)
+    }
+
+    #[cfg(not(feature = "simd-json"))]
+    {
+        serde_json::to_writer(writer, value).map_err(io::Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Fortune, Message, World};
+
+    #[test]
+    fn models_keep_their_wire_shape() {
+        let cases: [(&[u8], Vec<u8>); 3] = [
+            (
+                br#"{"message":"Hello, World!"}"#,
+                to_vec(&Message::HELLO_WORLD).unwrap(),
+            ),
+            (
+                br#"{"id":1,"randomNumber":42}"#,
+                to_vec(&World::new(1, 42)).unwrap(),
+            ),
+            (
+                br#"[{"id":2,"message":"<b>"}]"#,
+                to_vec(&[Fortune::new(2, "<b>")][..]).unwrap(),
+            ),
+        ];
+        for (expected, actual) in cases {
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn writer_matches_vec() {
+        let worlds = [World::new(1, 2), World::new(3, 4)];
+        let mut written = Vec::new();
+        to_writer(&mut written, &worlds[..]).unwrap();
+        assert_eq!(written, to_vec(&worlds[..]).unwrap());
+    }
+}
@@ -0,0 +1,27 @@
+//! Models, request parsing and rendering shared by the Rust TechEmpower
+//! implementations.
+//!
+//! Everything in here is written against the benchmark rules, so that the
+//! individual implementations do not have to re-derive them:
+//!
+//! - [`models`]: the `World`, `Fortune` and `Message` payloads.
+//! - [`query`]: the `queries` parameter parser, clamped to `1..=500`.
+//! - [`fortune`]: the request-time fortune, the sort rule and the HTML template.
+//! - [`random`]: random world ids in `1..=10_000`, and a per-worker generator.
+//! - [`update`]: the bulk update statement and the deduplicated ids bound to it.
+//! - [`json`]: serialization through `serde_json`, `sonic-rs` or `simd-json`.
+//! - `shutdown`: the shutdown signal and drain timeout, behind the `tokio`
+//!   feature.
+
+pub mod fortune;
+#[cfg(feature = "serde")]
+pub mod json;
+pub mod models;
+pub mod query;
+pub mod random;
+#[cfg(feature = "tokio")]
+pub mod shutdown;
+pub mod update;
+
+pub use models::{Fortune, Message, World};
+pub use random::{random_id, random_ids, WorldRng};
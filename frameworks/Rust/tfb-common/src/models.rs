@@ -0,0 +1,54 @@
+use std::borrow::Cow;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Payload of the JSON serialization test.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Message {
+    pub message: &'static str,
+}
+
+impl Message {
+    pub const HELLO_WORLD: Message = Message {
+        message: "Hello, World!",
+    };
+}
+
+/// A row of the `World` table.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct World {
+    pub id: i32,
+    #[cfg_attr(feature = "serde", serde(rename = "randomNumber"))]
+    pub random_number: i32,
+}
+
+impl World {
+    #[inline(always)]
+    pub const fn new(id: i32, random_number: i32) -> Self {
+        Self { id, random_number }
+    }
+}
+
+/// A row of the `Fortune` table.
+///
+/// The message is a `Cow` so that the fortune added at request time does not
+/// allocate.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Fortune {
+    pub id: i32,
+    pub message: Cow<'static, str>,
+}
+
+impl Fortune {
+    #[inline(always)]
+    pub fn new(id: i32, message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            id,
+            message: message.into(),
+        }
+    }
+}
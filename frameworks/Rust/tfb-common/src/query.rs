@@ -0,0 +1,250 @@
+//! Parsing of the `queries` parameter used by the multiple queries, updates
+//! and cached queries tests.
+//!
+//! The rules are the same for all three tests: the parameter may be missing,
+//! empty or not a number, in which case one query is performed, and numbers
+//! outside `1..=500` are clamped to that range.
+//!
+//! The parser works on the raw bytes of the query string and never
+//! allocates: it walks the `&`-separated pairs, compares the percent-decoded
+//! key against `q` and `queries` and folds the percent-decoded value into a
+//! saturating count.
+
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+
+/// Lower bound of the `queries` parameter.
+pub const MIN_QUERIES: usize = 1;
+/// Upper bound of the `queries` parameter.
+pub const MAX_QUERIES: usize = 500;
+
+/// Keys accepted for the `queries` parameter.
+const KEYS: [&[u8]; 2] = [b"q", b"queries"];
+
+/// Extract the clamped `queries` count from a raw query string, without the
+/// leading `?`.
+#[inline]
+pub fn queries(query: &str) -> usize {
+    count(find(query.as_bytes()).map(Decode::new))
+}
+
+/// Extract the clamped `queries` count from a request target such as
+/// `/queries?q=20`.
+#[inline]
+pub fn queries_in_path(path: &str) -> usize {
+    match path.split_once('?') {
+        Some((_, query)) => queries(query),
+        None => MIN_QUERIES,
+    }
+}
+
+/// Find the raw, still percent-encoded, value of the `queries` parameter in
+/// a query string. The first matching pair wins.
+#[inline]
+pub fn find(query: &[u8]) -> Option<&[u8]> {
+    query.split(|&b| b == b'&').find_map(|pair| {
+        let (key, value) = match pair.iter().position(|&b| b == b'=') {
+            Some(eq) => (&pair[..eq], &pair[eq + 1..]),
+            None => (pair, &[][..]),
+        };
+        KEYS.iter()
+            .any(|expected| Decode::new(key).eq(expected.iter().copied()))
+            .then_some(value)
+    })
+}
+
+/// Turn an already decoded `queries` value into a count in
+/// `MIN_QUERIES..=MAX_QUERIES`.
+#[inline]
+pub fn clamp(value: Option<&str>) -> usize {
+    count(value.map(str::bytes))
+}
+
+/// Fold the decoded bytes of a `queries` value into a clamped count.
+///
+/// Surrounding spaces and a sign are accepted; anything else that is not a
+/// digit makes the value invalid.
+fn count(value: Option<impl Iterator<Item = u8>>) -> usize {
+    let Some(value) = value else {
+        return MIN_QUERIES;
+    };
+
+    let mut value = value.skip_while(|&b| b == b' ').peekable();
+    let negative = value.next_if(|&b| b == b'-' || b == b'+') == Some(b'-');
+
+    match digits(value) {
+        Some(n) if !negative => n,
+        _ => MIN_QUERIES,
+    }
+}
+
+/// Parse at least one digit followed by optional spaces, saturating at
+/// `MAX_QUERIES` and never returning less than `MIN_QUERIES`.
+fn digits(mut value: impl Iterator<Item = u8>) -> Option<usize> {
+    let mut n = 0usize;
+    let mut any = false;
+    for b in value.by_ref() {
+        match b {
+            b'0'..=b'9' => {
+                n = (n * 10 + usize::from(b - b'0')).min(MAX_QUERIES);
+                any = true;
+            }
+            b' ' if any => break,
+            _ => return None,
+        }
+    }
+    if !any || !value.all(|b| b == b' ') {
+        return None;
+    }
+    Some(n.max(MIN_QUERIES))
+}
+
+/// Percent-decoding iterator over the bytes of a query-string component,
+/// with `+` standing for a space. Malformed escapes are passed through.
+struct Decode<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decode<'a> {
+    #[inline(always)]
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+}
+
+impl Iterator for Decode<'_> {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        let (&b, rest) = self.bytes.split_first()?;
+        self.bytes = rest;
+        match b {
+            b'+' => Some(b' '),
+            b'%' => match rest {
+                [hi, lo, tail @ ..] => match (hex(*hi), hex(*lo)) {
+                    (Some(hi), Some(lo)) => {
+                        self.bytes = tail;
+                        Some(hi << 4 | lo)
+                    }
+                    _ => Some(b),
+                },
+                _ => Some(b),
+            },
+            _ => Some(b),
+        }
+    }
+}
+
+#[inline(always)]
+fn hex(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Query-string extractor target for frameworks that deserialize the query
+/// with `serde` (`axum::extract::Query`, `actix_web::web::Query`, ...).
+#[cfg(feature = "serde")]
+#[derive(Debug, Default, Deserialize)]
+pub struct Params {
+    q: Option<String>,
+    queries: Option<String>,
+}
+
+#[cfg(feature = "serde")]
+impl Params {
+    /// The clamped number of queries requested.
+    #[inline(always)]
+    pub fn count(&self) -> usize {
+        clamp(self.q.as_deref().or(self.queries.as_deref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_strings() {
+        const CASES: [(&str, usize); 26] = [
+            // missing or empty
+            ("", 1),
+            ("q", 1),
+            ("q=", 1),
+            ("foo=1", 1),
+            // not a number
+            ("q=abc", 1),
+            ("q=1a", 1),
+            ("q=1%202", 1),
+            ("q=0x10", 1),
+            // out of range
+            ("q=-5", 1),
+            ("q=0", 1),
+            ("q=1", 1),
+            ("q=500", 500),
+            ("q=501", 500),
+            ("q=99999999999999999999999999999999", 500),
+            // percent-encoded and with a sign or spaces
+            ("q=%32%30", 20),
+            ("q=2%30", 20),
+            ("%71=7", 7),
+            ("q=%2B7", 7),
+            ("q=+12+", 12),
+            // position among other pairs
+            ("foo=1&q=20", 20),
+            ("xq=3&q=4", 4),
+            ("qq=3&queries=8", 8),
+            ("q=5&", 5),
+            ("&&q=6", 6),
+            ("q=3&q=4", 3),
+            ("q=%zz&q=4", 1),
+        ];
+        for (query, expected) in CASES {
+            assert_eq!(queries(query), expected, "{query:?}");
+        }
+    }
+
+    #[test]
+    fn paths() {
+        const CASES: [(&str, usize); 7] = [
+            ("/queries", 1),
+            ("/queries?", 1),
+            ("/queries?q=20", 20),
+            ("/queries?q=20&", 20),
+            ("/queries?q=600", 500),
+            ("/updates?queries=3", 3),
+            ("/cached-queries?foo=1&q=%35", 5),
+        ];
+        for (path, expected) in CASES {
+            assert_eq!(queries_in_path(path), expected, "{path:?}");
+        }
+    }
+
+    #[test]
+    fn decoded_values() {
+        assert_eq!(clamp(None), 1);
+        assert_eq!(clamp(Some("")), 1);
+        assert_eq!(clamp(Some("7")), 7);
+        assert_eq!(clamp(Some(" 7 ")), 7);
+        assert_eq!(clamp(Some("-7")), 1);
+        assert_eq!(clamp(Some("1000")), 500);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn params() {
+        let params = |q: Option<&str>, queries: Option<&str>| Params {
+            q: q.map(str::to_owned),
+            queries: queries.map(str::to_owned),
+        };
+        assert_eq!(Params::default().count(), 1);
+        assert_eq!(params(Some("9"), None).count(), 9);
+        assert_eq!(params(None, Some("9")).count(), 9);
+        assert_eq!(params(Some("2"), Some("9")).count(), 2);
+        assert_eq!(params(Some("x"), None).count(), 1);
+    }
+}
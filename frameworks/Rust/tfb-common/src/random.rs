@@ -0,0 +1,155 @@
+use std::{
+    cell::Cell,
+    hash::{BuildHasher, Hasher, RandomState},
+};
+
+use rand::{distr::Uniform, Rng};
+
+/// Number of rows in the `World` table.
+pub const WORLD_ROWS: i32 = 10_000;
+
+/// Generate a single integer in the range 1 to 10,000 (inclusive)
+#[inline(always)]
+pub fn random_id(rng: &mut impl Rng) -> i32 {
+    rng.random_range(1..=WORLD_ROWS)
+}
+
+/// Generate an iterator of integers in the range 1 to 10,000 (inclusive)
+#[inline(always)]
+pub fn random_ids<R: Rng>(
+    rng: &mut R,
+    count: usize,
+) -> impl Iterator<Item = i32> + use<'_, R> {
+    rng.sample_iter(Uniform::new_inclusive(1, WORLD_ROWS).unwrap())
+        .take(count)
+}
+
+/// A world id generator for one worker.
+///
+/// Servers that keep their state in a thread-local or `Rc`-shared struct can only
+/// reach it through `&self`. `WorldRng` keeps its state in a [`Cell`], so every call
+/// advances it; cloning a generator to get `&mut` access would replay the same
+/// sequence on every request. It is `!Sync`: create one per worker thread.
+///
+/// The generator is wyrand, and ids are drawn without modulo bias.
+#[derive(Debug)]
+pub struct WorldRng {
+    state: Cell<u64>,
+}
+
+impl WorldRng {
+    /// A generator seeded from the standard library's per-process random keys,
+    /// different for every instance.
+    pub fn new() -> Self {
+        Self::with_seed(RandomState::new().build_hasher().finish())
+    }
+
+    /// A generator with a fixed seed, for reproducible sequences.
+    pub const fn with_seed(seed: u64) -> Self {
+        Self {
+            state: Cell::new(seed),
+        }
+    }
+
+    #[inline]
+    pub fn next_u64(&self) -> u64 {
+        let state = self.state.get().wrapping_add(0xa076_1d64_78bd_642f);
+        self.state.set(state);
+        let t = u128::from(state) * u128::from(state ^ 0xe703_7ed1_a0b4_28db);
+        (t as u64) ^ (t >> 64) as u64
+    }
+
+    /// A world id in `1..=WORLD_ROWS`.
+    #[inline]
+    pub fn id(&self) -> i32 {
+        self.below(WORLD_ROWS as u32) as i32 + 1
+    }
+
+    /// `count` world ids in `1..=WORLD_ROWS`.
+    #[inline]
+    pub fn ids(&self, count: usize) -> impl Iterator<Item = i32> + '_ {
+        (0..count).map(|_| self.id())
+    }
+
+    /// A number in `0..n`, using Lemire's multiply-and-reject method.
+    #[inline]
+    fn below(&self, n: u32) -> u32 {
+        let mut m = u64::from(self.next_u32()) * u64::from(n);
+        if (m as u32) < n {
+            let threshold = n.wrapping_neg() % n;
+            while (m as u32) < threshold {
+                m = u64::from(self.next_u32()) * u64::from(n);
+            }
+        }
+        (m >> 32) as u32
+    }
+
+    #[inline(always)]
+    fn next_u32(&self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+}
+
+impl Default for WorldRng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: usize = 1_000_000;
+    const BUCKETS: usize = 100;
+
+    #[test]
+    fn ids_stay_in_the_table() {
+        let rng = WorldRng::with_seed(1);
+        let (mut min, mut max) = (i32::MAX, i32::MIN);
+        for id in rng.ids(SAMPLES) {
+            min = min.min(id);
+            max = max.max(id);
+        }
+        assert_eq!((min, max), (1, WORLD_ROWS));
+    }
+
+    #[test]
+    fn state_advances_through_shared_reference() {
+        let rng = WorldRng::with_seed(2);
+        let shared = &rng;
+        let mut ids: Vec<i32> = (0..1_000).map(|_| shared.id()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        // 1000 draws from 10,000 ids collide about 48 times on average.
+        assert!(ids.len() > 900, "only {} distinct ids", ids.len());
+    }
+
+    #[test]
+    fn independent_instances_differ() {
+        let a = WorldRng::new();
+        let b = WorldRng::new();
+        assert_ne!(a.ids(16).collect::<Vec<_>>(), b.ids(16).collect::<Vec<_>>());
+    }
+
+    /// Pearson's chi-squared test over buckets of 100 consecutive ids.
+    #[test]
+    fn ids_are_uniform() {
+        for seed in [3, 0xdead_beef, u64::MAX] {
+            let rng = WorldRng::with_seed(seed);
+            let mut counts = [0u32; BUCKETS];
+            let width = WORLD_ROWS as usize / BUCKETS;
+            for id in rng.ids(SAMPLES) {
+                counts[(id as usize - 1) / width] += 1;
+            }
+
+            let expected = (SAMPLES / BUCKETS) as f64;
+            let chi2: f64 = counts
+                .iter()
+                .map(|&count| (f64::from(count) - expected).powi(2) / expected)
+                .sum();
+            // Critical value for 99 degrees of freedom at p = 0.001.
+            assert!(chi2 < 148.2, "seed {seed}: chi-squared {chi2:.1}");
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! Graceful shutdown of the tokio-based implementations.
+
+use std::time::Duration;
+
+/// How long live connections get to finish their in-flight requests once a
+/// shutdown signal was received. Docker sends `SIGKILL` ten seconds after
+/// `SIGTERM`, so this stays well below that.
+pub const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Resolves on `Ctrl+C` or, on Unix, `SIGTERM`.
+///
+/// Every runtime awaiting it gets the signal, so a server running one
+/// current-thread runtime per core can call it once per runtime.
+pub async fn signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
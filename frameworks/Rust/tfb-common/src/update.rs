@@ -0,0 +1,121 @@
+//! The updates test: the bulk statement and the ids and numbers bound to it.
+//!
+//! Every drawn world is updated by a single statement. Its ids are bound sorted
+//! and without duplicates, so that concurrent requests lock their rows in the
+//! same order and cannot deadlock, and so that a world drawn more than once
+//! gets exactly one new number.
+
+use crate::models::World;
+
+/// Update the `world` rows whose ids are bound as `$1` to the numbers bound as
+/// `$2`, from the two arrays returned by [`dedup_updates`].
+pub const UPDATE_WORLDS: &str = "UPDATE world SET randomnumber = new.randomnumber \
+    FROM UNNEST($1::int[], $2::int[]) AS new(id, randomnumber) \
+    WHERE world.id = new.id";
+
+/// The ids and numbers to bind to [`UPDATE_WORLDS`], from worlds holding their
+/// new numbers: sorted by id and without duplicates.
+///
+/// A world drawn more than once keeps the first of its numbers, in `worlds` as
+/// in the table.
+#[inline]
+pub fn dedup_updates(worlds: &mut [World]) -> (Vec<i32>, Vec<i32>) {
+    dedup_updates_with(worlds, |world| (world.id, &mut world.random_number))
+}
+
+/// [`dedup_updates`] for an implementation's own world type: `fields` returns
+/// the id of a world and its number.
+pub fn dedup_updates_with<T>(
+    worlds: &mut [T],
+    mut fields: impl FnMut(&mut T) -> (i32, &mut i32),
+) -> (Vec<i32>, Vec<i32>) {
+    let mut updates: Vec<(i32, i32)> = worlds
+        .iter_mut()
+        .map(|world| {
+            let (id, number) = fields(world);
+            (id, *number)
+        })
+        .collect();
+    // stable, so that the first number of a repeated id is the one kept
+    updates.sort_by_key(|&(id, _)| id);
+    updates.dedup_by_key(|&mut (id, _)| id);
+
+    for world in worlds.iter_mut() {
+        let (id, number) = fields(world);
+        if let Ok(i) = updates.binary_search_by_key(&id, |&(id, _)| id) {
+            *number = updates[i].1;
+        }
+    }
+    updates.into_iter().unzip()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_the_ids() {
+        let mut worlds = [World::new(3, 30), World::new(1, 10), World::new(2, 20)];
+        let (ids, numbers) = dedup_updates(&mut worlds);
+
+        assert_eq!(ids, [1, 2, 3]);
+        assert_eq!(numbers, [10, 20, 30]);
+        // the response keeps the order of the draws
+        assert_eq!(
+            worlds,
+            [World::new(3, 30), World::new(1, 10), World::new(2, 20)]
+        );
+    }
+
+    #[test]
+    fn a_repeated_world_keeps_its_first_number() {
+        let mut worlds = [
+            World::new(7, 1),
+            World::new(2, 5),
+            World::new(7, 2),
+            World::new(7, 3),
+        ];
+        let (ids, numbers) = dedup_updates(&mut worlds);
+
+        assert_eq!(ids, [2, 7]);
+        assert_eq!(numbers, [5, 1]);
+        assert_eq!(
+            worlds,
+            [
+                World::new(7, 1),
+                World::new(2, 5),
+                World::new(7, 1),
+                World::new(7, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn no_worlds() {
+        let (ids, numbers) = dedup_updates(&mut []);
+        assert!(ids.is_empty() && numbers.is_empty());
+    }
+
+    #[test]
+    fn own_world_type() {
+        struct Row {
+            id: i32,
+            random_number: i32,
+        }
+        let mut rows = [
+            Row {
+                id: 4,
+                random_number: 40,
+            },
+            Row {
+                id: 4,
+                random_number: 41,
+            },
+        ];
+        let (ids, numbers) =
+            dedup_updates_with(&mut rows, |row| (row.id, &mut row.random_number));
+
+        assert_eq!((ids, numbers), (vec![4], vec![40]));
+        assert!(rows.iter().all(|row| row.random_number == 40));
+    }
+}
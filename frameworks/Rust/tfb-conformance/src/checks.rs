@@ -0,0 +1,781 @@
+//! The per-test rules, mirroring `toolset/test_types`.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    net::SocketAddr,
+    str::FromStr,
+};
+
+use serde_json::Value;
+use tfb_common::{
+    fortune::ADDITIONAL_FORTUNE,
+    query::{MAX_QUERIES, MIN_QUERIES},
+    random::WORLD_ROWS,
+};
+
+use crate::http::{self, Response};
+
+/// The benchmark test types, named as in `benchmark_config.json`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TestType {
+    Json,
+    Plaintext,
+    Db,
+    Query,
+    Fortune,
+    Update,
+    CachedQuery,
+}
+
+impl TestType {
+    pub const ALL: [TestType; 7] = [
+        TestType::Json,
+        TestType::Plaintext,
+        TestType::Db,
+        TestType::Query,
+        TestType::Fortune,
+        TestType::Update,
+        TestType::CachedQuery,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TestType::Json => "json",
+            TestType::Plaintext => "plaintext",
+            TestType::Db => "db",
+            TestType::Query => "query",
+            TestType::Fortune => "fortune",
+            TestType::Update => "update",
+            TestType::CachedQuery => "cached_query",
+        }
+    }
+
+    /// Run every check of this test type, returning the problems found.
+    pub fn check(self, addr: SocketAddr) -> Vec<String> {
+        let mut problems = Problems::default();
+        match self {
+            TestType::Json => json(addr, &mut problems),
+            TestType::Plaintext => plaintext(addr, &mut problems),
+            TestType::Db => db(addr, &mut problems),
+            TestType::Query => multiple(addr, "/queries", &mut problems),
+            TestType::Fortune => fortunes(addr, &mut problems),
+            TestType::Update => updates(addr, &mut problems),
+            TestType::CachedQuery => multiple(addr, "/cached-queries", &mut problems),
+        }
+        problems.0
+    }
+}
+
+impl FromStr for TestType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TestType::ALL
+            .into_iter()
+            .find(|test| test.name() == s)
+            .ok_or_else(|| format!("unknown test type {s:?}"))
+    }
+}
+
+impl fmt::Display for TestType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The `queries` values the verifier sends, with the expected row counts.
+const QUERY_CASES: [(&str, usize); 8] = [
+    ("", MIN_QUERIES),
+    ("0", MIN_QUERIES),
+    ("foo", MIN_QUERIES),
+    ("-1", MIN_QUERIES),
+    ("1", 1),
+    ("20", 20),
+    ("501", MAX_QUERIES),
+    ("1000", MAX_QUERIES),
+];
+
+/// Rounds of `/queries?q=500` spent reading back the rows `/updates` wrote,
+/// and the number of read-back rows that is enough to trust the updates.
+const READ_BACK_ROUNDS: usize = 20;
+const READ_BACK_ROWS: usize = 10;
+
+/// The `Fortune` table as created by `toolset/databases`.
+const FORTUNES: [(i64, &str); 12] = [
+    (1, "fortune: No such file or directory"),
+    (
+        2,
+        "A computer scientist is someone who fixes things that aren't broken.",
+    ),
+    (3, "After enough decimal places, nobody gives a damn."),
+    (
+        4,
+        "A bad random number generator: 1, 1, 1, 1, 1, 4.33e+67, 1, 1, 1",
+    ),
+    (
+        5,
+        "A computer program does what you tell it to do, not what you want it to do.",
+    ),
+    (
+        6,
+        "Emacs is a nice operating system, but I prefer UNIX. — Tom Christaensen",
+    ),
+    (7, "Any program that runs right is obsolete."),
+    (
+        8,
+        "A list is only as strong as its weakest link. — Donald Knuth",
+    ),
+    (9, "Feature: A bug with seniority."),
+    (10, "Computers make very fast, very accurate mistakes."),
+    (
+        11,
+        "<script>alert(\"This should not be displayed in a browser alert box.\");</script>",
+    ),
+    (12, "フレームワークのベンチマーク"),
+];
+
+#[derive(Default)]
+struct Problems(Vec<String>);
+
+impl Problems {
+    fn push(&mut self, path: &str, problem: impl fmt::Display) {
+        self.0.push(format!("{path}: {problem}"));
+    }
+
+    /// Fetch `path` and check the status and the headers common to all tests.
+    fn get(
+        &mut self,
+        addr: SocketAddr,
+        path: &str,
+        content_type: &str,
+    ) -> Option<Response> {
+        let res = match http::get(addr, path) {
+            Ok(res) => res,
+            Err(err) => {
+                self.push(path, format_args!("request failed: {err}"));
+                return None;
+            }
+        };
+
+        if res.status != 200 {
+            self.push(path, format_args!("status {}, expected 200", res.status));
+            return None;
+        }
+        if res.header("server").is_none() {
+            self.push(path, "missing Server header");
+        }
+        match res.header("date") {
+            None => self.push(path, "missing Date header"),
+            Some(date) if !is_http_date(date) => self.push(
+                path,
+                format_args!("Date header {date:?} is not an HTTP date"),
+            ),
+            Some(_) => {}
+        }
+        if res.header("content-length").is_none()
+            && res.header("transfer-encoding").is_none()
+        {
+            self.push(path, "missing Content-Length and Transfer-Encoding headers");
+        }
+        match res.header("content-type") {
+            None => self.push(path, "missing Content-Type header"),
+            Some(ct) if !ct.to_ascii_lowercase().starts_with(content_type) => self.push(
+                path,
+                format_args!("Content-Type {ct:?}, expected {content_type:?}"),
+            ),
+            Some(_) => {}
+        }
+
+        Some(res)
+    }
+
+    fn get_json(&mut self, addr: SocketAddr, path: &str) -> Option<Value> {
+        let res = self.get(addr, path, "application/json")?;
+        match serde_json::from_slice(&res.body) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.push(path, format_args!("invalid JSON: {err}"));
+                None
+            }
+        }
+    }
+
+    /// Check that `value` is a `World` row.
+    fn world(&mut self, path: &str, value: &Value) {
+        let Some(object) = value.as_object() else {
+            self.push(path, format_args!("expected a world object, found {value}"));
+            return;
+        };
+        if object.len() != 2 {
+            self.push(
+                path,
+                format_args!("expected exactly id and randomNumber in {value}"),
+            );
+        }
+        for key in ["id", "randomnumber"] {
+            let field = object
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v);
+            match field.and_then(Value::as_i64) {
+                Some(n) if (1..=i64::from(WORLD_ROWS)).contains(&n) => {}
+                Some(n) => {
+                    self.push(path, format_args!("{key} {n} out of 1..={WORLD_ROWS}"))
+                }
+                None => self.push(
+                    path,
+                    format_args!("{key} missing or not an integer in {value}"),
+                ),
+            }
+        }
+    }
+}
+
+fn json(addr: SocketAddr, problems: &mut Problems) {
+    let path = "/json";
+    if let Some(value) = problems.get_json(addr, path)
+        && value != serde_json::json!({ "message": "Hello, World!" })
+    {
+        problems.push(path, format_args!("unexpected body {value}"));
+    }
+}
+
+fn plaintext(addr: SocketAddr, problems: &mut Problems) {
+    let path = "/plaintext";
+    if let Some(res) = problems.get(addr, path, "text/plain")
+        && res.body != b"Hello, World!"
+    {
+        problems.push(
+            path,
+            format_args!("unexpected body {:?}", String::from_utf8_lossy(&res.body)),
+        );
+    }
+}
+
+fn db(addr: SocketAddr, problems: &mut Problems) {
+    let path = "/db";
+    if let Some(value) = problems.get_json(addr, path) {
+        problems.world(path, &value);
+    }
+}
+
+fn multiple(addr: SocketAddr, route: &str, problems: &mut Problems) {
+    let bare = (route.to_owned(), MIN_QUERIES);
+    let cases = QUERY_CASES
+        .iter()
+        .map(|(q, count)| (format!("{route}?q={q}"), *count));
+
+    for (path, count) in std::iter::once(bare).chain(cases) {
+        let Some(value) = problems.get_json(addr, &path) else {
+            continue;
+        };
+        let Some(worlds) = value.as_array() else {
+            problems.push(&path, format_args!("expected an array, found {value}"));
+            continue;
+        };
+        if worlds.len() != count {
+            problems.push(
+                &path,
+                format_args!("{} worlds, expected {count}", worlds.len()),
+            );
+        }
+        for world in worlds {
+            problems.world(&path, world);
+        }
+    }
+}
+
+fn updates(addr: SocketAddr, problems: &mut Problems) {
+    multiple(addr, "/updates", problems);
+    persisted(addr, problems);
+}
+
+/// Check that `/updates` wrote the numbers it returned: read worlds back
+/// through `/queries` until enough of the updated ids come up.
+fn persisted(addr: SocketAddr, problems: &mut Problems) {
+    let path = format!("/updates?q={MAX_QUERIES}");
+    let Some(value) = problems.get_json(addr, &path) else {
+        return;
+    };
+    let mut updated = HashMap::new();
+    let mut repeated = HashSet::new();
+    for (id, number) in worlds(&value) {
+        if updated.insert(id, number).is_some() {
+            repeated.insert(id);
+        }
+    }
+    // the last write of a repeated id is not necessarily the last one listed
+    updated.retain(|id, _| !repeated.contains(id));
+    if updated.is_empty() {
+        // the shape of the response was already reported by `multiple`
+        return;
+    }
+
+    let query = format!("/queries?q={MAX_QUERIES}");
+    let mut confirmed = 0;
+    for _ in 0..READ_BACK_ROUNDS {
+        let Some(value) = problems.get_json(addr, &query) else {
+            return;
+        };
+        for (id, number) in worlds(&value) {
+            match updated.get(&id) {
+                Some(&expected) if expected != number => {
+                    return problems.push(
+                        &path,
+                        format_args!(
+                            "world {id} has randomNumber {number} in {query}, \
+                             but the update returned {expected}"
+                        ),
+                    );
+                }
+                Some(_) => confirmed += 1,
+                None => {}
+            }
+        }
+        if confirmed >= READ_BACK_ROWS {
+            return;
+        }
+    }
+    if confirmed == 0 {
+        problems.push(
+            &path,
+            format_args!(
+                "no updated world came back from {query}, updates not confirmed"
+            ),
+        );
+    }
+}
+
+/// The `(id, randomNumber)` pairs of a JSON array of worlds, skipping the
+/// malformed ones.
+fn worlds(value: &Value) -> impl Iterator<Item = (i64, i64)> + '_ {
+    value.as_array().into_iter().flatten().filter_map(|world| {
+        let object = world.as_object()?;
+        let field = |key: &str| {
+            object
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))?
+                .1
+                .as_i64()
+        };
+        Some((field("id")?, field("randomnumber")?))
+    })
+}
+
+fn fortunes(addr: SocketAddr, problems: &mut Problems) {
+    let path = "/fortunes";
+    let Some(res) = problems.get(addr, path, "text/html") else {
+        return;
+    };
+    let html = match res.text() {
+        Ok(html) => html,
+        Err(err) => return problems.push(path, err),
+    };
+
+    if !html
+        .trim_start()
+        .to_ascii_lowercase()
+        .starts_with("<!doctype html>")
+    {
+        problems.push(path, "body does not start with <!DOCTYPE html>");
+    }
+    if html.contains("<script>") {
+        problems.push(path, "fortune messages are not HTML-escaped");
+    }
+
+    let mut expected: Vec<(i64, &str)> = FORTUNES.to_vec();
+    expected.push((0, ADDITIONAL_FORTUNE));
+    expected.sort_by(|a, b| a.1.cmp(b.1));
+
+    let rows = table_rows(html);
+    if rows.len() != expected.len() {
+        problems.push(
+            path,
+            format_args!("{} rows, expected {}", rows.len(), expected.len()),
+        );
+    }
+    for (row, (id, message)) in rows.iter().zip(&expected) {
+        if row.0.parse() != Ok(*id) || row.1 != *message {
+            problems.push(
+                path,
+                format_args!("row {row:?}, expected ({id}, {message:?})"),
+            );
+        }
+    }
+}
+
+/// The unescaped `(id, message)` cells of each `<tr>` with `<td>` cells.
+fn table_rows(html: &str) -> Vec<(String, String)> {
+    let mut cells = Vec::new();
+    let mut rest = html;
+    while let Some(start) = find_ignore_case(rest, "<td>") {
+        rest = &rest[start + 4..];
+        let end = find_ignore_case(rest, "</td>").unwrap_or(rest.len());
+        cells.push(unescape(rest[..end].trim()));
+        rest = &rest[end..];
+    }
+    cells
+        .chunks_exact(2)
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
+        .collect()
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+/// Decode the named and numeric character references used for escaping.
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').and_then(|semi| {
+            let c = match &rest[1..semi] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                entity => {
+                    let code =
+                        match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                            None => entity.strip_prefix('#')?.parse().ok()?,
+                        };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, semi + 1))
+        });
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Check the IMF-fixdate format, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+fn is_http_date(date: &str) -> bool {
+    const DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov",
+        "Dec",
+    ];
+
+    let parts: Vec<&str> = date.split(' ').collect();
+    let digits =
+        |s: &str, len: usize| s.len() == len && s.bytes().all(|b| b.is_ascii_digit());
+
+    matches!(
+        parts.as_slice(),
+        [day, dd, month, yyyy, time, "GMT"]
+            if day.strip_suffix(',').is_some_and(|day| DAYS.contains(&day))
+                && digits(dd, 2)
+                && MONTHS.contains(month)
+                && digits(yyyy, 4)
+                && time.split(':').all(|part| digits(part, 2))
+                && time.len() == 8
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::{Ipv4Addr, TcpListener},
+        sync::Mutex,
+        thread,
+    };
+
+    use tfb_common::{Fortune, fortune, query};
+
+    use super::*;
+
+    const DATE: &str = "Sun, 06 Nov 1994 08:49:37 GMT";
+
+    /// Answer every request on a local port with the raw response `respond`
+    /// returns for its path, one connection at a time.
+    fn stub(respond: impl Fn(&str) -> String + Send + 'static) -> SocketAddr {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = BufReader::new(stream.unwrap());
+                let mut request_line = String::new();
+                stream.read_line(&mut request_line).unwrap();
+                let mut line = String::new();
+                while stream.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+                let path = request_line.split(' ').nth(1).unwrap();
+                let _ = stream.get_mut().write_all(respond(path).as_bytes());
+            }
+        });
+        addr
+    }
+
+    /// A 200 response with every header the rules ask for.
+    fn ok(content_type: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nServer: stub\r\nDate: {DATE}\r\n\
+             Content-Type: {content_type}\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    fn json_worlds(worlds: impl Iterator<Item = (i64, i64)>) -> String {
+        let worlds: Vec<Value> = worlds
+            .map(|(id, number)| serde_json::json!({ "id": id, "randomNumber": number }))
+            .collect();
+        ok("application/json", &Value::from(worlds).to_string())
+    }
+
+    fn fortunes_page() -> String {
+        let mut fortunes = FORTUNES
+            .iter()
+            .map(|&(id, message)| Fortune::new(id as i32, message))
+            .collect();
+        fortune::prepare(&mut fortunes);
+        fortune::render(&fortunes)
+    }
+
+    /// An implementation of every test over worlds numbered after their id.
+    /// `/queries` and `/updates` return the first `q` worlds, so that the
+    /// updates are read back. With `persist` unset, updates are not stored.
+    fn benchmark(persist: bool) -> SocketAddr {
+        let table = Mutex::new((1..=i64::from(WORLD_ROWS)).collect::<Vec<_>>());
+        stub(move |path| {
+            let (route, _) = path.split_once('?').unwrap_or((path, ""));
+            let count = query::queries_in_path(path) as i64;
+            let mut table = table.lock().unwrap();
+            match route {
+                "/json" => ok("application/json", r#"{"message":"Hello, World!"}"#),
+                "/plaintext" => ok("text/plain", "Hello, World!"),
+                "/db" => ok("application/json", r#"{"id":1,"randomNumber":1}"#),
+                "/queries" | "/cached-queries" => {
+                    json_worlds((1..=count).map(|id| (id, table[id as usize - 1])))
+                }
+                "/updates" => {
+                    let mut updated = table.clone();
+                    for id in 1..=count {
+                        let number = &mut updated[id as usize - 1];
+                        *number = *number % i64::from(WORLD_ROWS) + 1;
+                    }
+                    let response = json_worlds(
+                        (1..=count).map(|id| (id, updated[id as usize - 1])),
+                    );
+                    if persist {
+                        *table = updated;
+                    }
+                    response
+                }
+                "/fortunes" => ok("text/html; charset=utf-8", &fortunes_page()),
+                _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_owned(),
+            }
+        })
+    }
+
+    #[test]
+    fn conforming_implementation_passes() {
+        let addr = benchmark(true);
+        for test in TestType::ALL {
+            assert_eq!(test.check(addr), Vec::<String>::new(), "{test}");
+        }
+    }
+
+    #[test]
+    fn get_reports_status_and_headers() {
+        let addr = stub(|path| match path {
+            "/error" => {
+                "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\n\r\n"
+                    .to_owned()
+            }
+            "/bare" => "HTTP/1.1 200 OK\r\nDate: yesterday\r\n\r\n".to_owned(),
+            _ => ok("text/html", "<!DOCTYPE html>"),
+        });
+
+        let mut problems = Problems::default();
+        assert!(problems.get(addr, "/error", "text/plain").is_none());
+        assert!(problems.get(addr, "/bare", "text/plain").is_some());
+        assert!(problems.get(addr, "/html", "application/json").is_some());
+        assert_eq!(
+            problems.0,
+            [
+                "/error: status 500, expected 200",
+                "/bare: missing Server header",
+                "/bare: Date header \"yesterday\" is not an HTTP date",
+                "/bare: missing Content-Length and Transfer-Encoding headers",
+                "/bare: missing Content-Type header",
+                "/html: Content-Type \"text/html\", expected \"application/json\"",
+            ]
+        );
+
+        let mut problems = Problems::default();
+        let closed = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = closed.local_addr().unwrap();
+        drop(closed);
+        assert!(problems.get(addr, "/json", "application/json").is_none());
+        assert!(problems.0[0].starts_with("/json: request failed: "));
+    }
+
+    #[test]
+    fn multiple_reports_counts_and_worlds() {
+        // `q` is not clamped, and the last world is out of range
+        let addr = stub(|path| {
+            let count = path
+                .split_once("q=")
+                .and_then(|(_, q)| q.parse().ok())
+                .unwrap_or(1);
+            json_worlds((1..=count).map(|id| (id, if id == 20 { 0 } else { id })))
+        });
+
+        let problems = TestType::Query.check(addr);
+        assert_eq!(
+            problems,
+            [
+                "/queries?q=0: 0 worlds, expected 1",
+                "/queries?q=-1: 0 worlds, expected 1",
+                "/queries?q=20: randomnumber 0 out of 1..=10000",
+                "/queries?q=501: 501 worlds, expected 500",
+                "/queries?q=501: randomnumber 0 out of 1..=10000",
+                "/queries?q=1000: 1000 worlds, expected 500",
+                "/queries?q=1000: randomnumber 0 out of 1..=10000",
+            ]
+        );
+    }
+
+    #[test]
+    fn multiple_reports_malformed_bodies() {
+        let addr = stub(|path| match path {
+            "/cached-queries" => ok("application/json", "{\"id\":1,\"randomNumber\":1}"),
+            "/cached-queries?q=" => ok("application/json", "[{\"id\":1}]"),
+            "/cached-queries?q=0" => ok("application/json", "[1"),
+            _ => json_worlds(std::iter::once((1, 1))),
+        });
+
+        let problems = TestType::CachedQuery.check(addr);
+        assert_eq!(
+            problems[0],
+            "/cached-queries: expected an array, found {\"id\":1,\"randomNumber\":1}"
+        );
+        assert_eq!(
+            problems[1],
+            "/cached-queries?q=: expected exactly id and randomNumber in {\"id\":1}"
+        );
+        assert_eq!(
+            problems[2],
+            "/cached-queries?q=: randomnumber missing or not an integer in {\"id\":1}"
+        );
+        assert!(problems[3].starts_with("/cached-queries?q=0: invalid JSON: "));
+    }
+
+    #[test]
+    fn persisted_reports_lost_updates() {
+        let problems = TestType::Update.check(benchmark(false));
+        assert_eq!(
+            problems,
+            [
+                "/updates?q=500: world 1 has randomNumber 1 in /queries?q=500, \
+              but the update returned 2"
+            ]
+        );
+    }
+
+    #[test]
+    fn fortunes_reports_escaping_rows_and_order() {
+        let unescaped = stub(|_| {
+            ok(
+                "text/html",
+                &fortunes_page().replace("&lt;script&gt;", "<script>"),
+            )
+        });
+        let problems = TestType::Fortune.check(unescaped);
+        assert_eq!(
+            problems,
+            ["/fortunes: fortune messages are not HTML-escaped"]
+        );
+
+        // the request-time fortune is missing, and the others are sorted by id
+        let unsorted = stub(|_| {
+            let fortunes: Vec<Fortune> = FORTUNES
+                .iter()
+                .map(|&(id, message)| Fortune::new(id as i32, message))
+                .collect();
+            ok("text/html", &fortune::render(&fortunes))
+        });
+        let problems = TestType::Fortune.check(unsorted);
+        assert_eq!(problems[0], "/fortunes: 12 rows, expected 13");
+        assert_eq!(
+            problems[1],
+            format!(
+                "/fortunes: row (\"1\", {:?}), expected (11, {:?})",
+                FORTUNES[0].1, FORTUNES[10].1
+            )
+        );
+
+        let plain = stub(|_| ok("text/html", "<html><body></body></html>"));
+        assert_eq!(
+            TestType::Fortune.check(plain),
+            [
+                "/fortunes: body does not start with <!DOCTYPE html>",
+                "/fortunes: 0 rows, expected 13",
+            ]
+        );
+    }
+
+    #[test]
+    fn reference_fortunes_pass() {
+        let mut fortunes = FORTUNES
+            .iter()
+            .map(|&(id, message)| Fortune::new(id as i32, message))
+            .collect();
+        fortune::prepare(&mut fortunes);
+        let html = fortune::render(&fortunes);
+
+        let rows = table_rows(&html);
+        assert_eq!(rows.len(), FORTUNES.len() + 1);
+        assert_eq!(rows[0], ("11".to_owned(), FORTUNES[10].1.to_owned()));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn unescape_variants() {
+        assert_eq!(
+            unescape("&lt;a&gt; &amp; &quot;&#39;&#x27;&apos;"),
+            "<a> & \"'''"
+        );
+        assert_eq!(unescape("a & b &unknown;"), "a & b &unknown;");
+    }
+
+    #[test]
+    fn world_pairs() {
+        let value = serde_json::json!([
+            { "id": 1, "randomNumber": 2 },
+            { "id": 3, "randomnumber": 4 },
+            { "id": 5 },
+            "6",
+        ]);
+        assert_eq!(worlds(&value).collect::<Vec<_>>(), [(1, 2), (3, 4)]);
+    }
+
+    #[test]
+    fn http_dates() {
+        assert!(is_http_date("Sun, 06 Nov 1994 08:49:37 GMT"));
+        assert!(!is_http_date("Sunday, 06-Nov-94 08:49:37 GMT"));
+        assert!(!is_http_date("Sun, 6 Nov 1994 08:49:37 GMT"));
+    }
+}
@@ -0,0 +1,14 @@
+//! Spec-conformance harness for the Rust TechEmpower implementations.
+//!
+//! The harness boots an implementation binary (or attaches to one that is
+//! already running), sends the requests the benchmark verifier sends and
+//! checks the responses against the test rules. See `tests/conformance.rs`
+//! for the entry point and `README.md` for the environment it reads.
+
+pub mod checks;
+pub mod http;
+pub mod server;
+
+pub use checks::TestType;
+pub use http::Response;
+pub use server::Server;
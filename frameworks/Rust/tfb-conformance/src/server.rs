@@ -0,0 +1,98 @@
+//! Booting the implementation under test.
+
+use std::{
+    env,
+    net::{Ipv4Addr, SocketAddr, TcpStream},
+    process::{Child, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+const DEFAULT_PORT: u16 = 8080;
+const DEFAULT_STARTUP_TIMEOUT: u64 = 30;
+
+/// An implementation listening on `addr`, killed on drop if it was spawned
+/// by the harness.
+pub struct Server {
+    pub addr: SocketAddr,
+    child: Option<Child>,
+}
+
+impl Server {
+    /// Start the server described by the environment:
+    ///
+    /// - `TFB_BINARY`: implementation binary to spawn. The database settings
+    ///   it needs (`POSTGRES_URL`, `DATABASE_URL`, ...) are inherited from the
+    ///   harness environment.
+    /// - `TFB_ARGS`: whitespace-separated arguments for the binary.
+    /// - `TFB_PORT`: port the implementation listens on, 8080 by default.
+    /// - `TFB_STARTUP_TIMEOUT`: seconds to wait for the port to accept
+    ///   connections, 30 by default.
+    ///
+    /// Without `TFB_BINARY` the harness attaches to a server already
+    /// listening on `TFB_PORT`. Returns `None` when neither is available.
+    pub fn from_env() -> Result<Option<Server>, String> {
+        let port = env_parse("TFB_PORT", DEFAULT_PORT)?;
+        let timeout = env_parse("TFB_STARTUP_TIMEOUT", DEFAULT_STARTUP_TIMEOUT)?;
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+
+        let Ok(binary) = env::var("TFB_BINARY") else {
+            return Ok(TcpStream::connect(addr)
+                .is_ok()
+                .then_some(Server { addr, child: None }));
+        };
+
+        let args = env::var("TFB_ARGS").unwrap_or_default();
+        let child = Command::new(&binary)
+            .args(args.split_whitespace())
+            .stdin(Stdio::null())
+            .spawn()
+            .map_err(|err| format!("cannot spawn {binary}: {err}"))?;
+
+        let mut server = Server {
+            addr,
+            child: Some(child),
+        };
+        server.wait_ready(Duration::from_secs(timeout))?;
+        Ok(Some(server))
+    }
+
+    fn wait_ready(&mut self, timeout: Duration) -> Result<(), String> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(child) = &mut self.child
+                && let Ok(Some(status)) = child.try_wait()
+            {
+                return Err(format!("server exited during startup: {status}"));
+            }
+            if TcpStream::connect(self.addr).is_ok() {
+                return Ok(());
+            }
+            if Instant::now() > deadline {
+                return Err(format!(
+                    "server not listening on {} after {timeout:?}",
+                    self.addr
+                ));
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+fn env_parse<T: std::str::FromStr>(key: &str, default: T) -> Result<T, String> {
+    match env::var(key) {
+        Ok(value) => value
+            .parse()
+            .map_err(|_| format!("could not parse {key}={value:?}")),
+        Err(_) => Ok(default),
+    }
+}
@@ -0,0 +1,50 @@
+//! Runs the benchmark rules against the implementation described by the
+//! environment, see `Server::from_env`. `TFB_TESTS` selects the test types to
+//! check as a comma-separated list (`json,db,query,...`), all of them by
+//! default.
+//!
+//! The test is ignored by default, since it needs a running implementation:
+//! run it with `cargo test -- --ignored`. It then fails if no server is found.
+
+use std::env;
+
+use tfb_conformance::{Server, TestType};
+
+fn selected_tests() -> Vec<TestType> {
+    match env::var("TFB_TESTS") {
+        Ok(tests) => tests
+            .split(',')
+            .map(str::trim)
+            .filter(|test| !test.is_empty())
+            .map(|test| {
+                test.parse()
+                    .unwrap_or_else(|err| panic!("TFB_TESTS: {err}"))
+            })
+            .collect(),
+        Err(_) => TestType::ALL.to_vec(),
+    }
+}
+
+#[test]
+#[ignore = "needs TFB_BINARY or a server on TFB_PORT"]
+fn conformance() {
+    let tests = selected_tests();
+
+    let server = match Server::from_env() {
+        Ok(Some(server)) => server,
+        Ok(None) => panic!("no server: set TFB_BINARY or start a server on TFB_PORT"),
+        Err(err) => panic!("{err}"),
+    };
+
+    let mut failures = Vec::new();
+    for test in tests {
+        let problems = test.check(server.addr);
+        if problems.is_empty() {
+            eprintln!("{test}: ok");
+        } else {
+            failures.push(format!("{test}:\n  {}", problems.join("\n  ")));
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
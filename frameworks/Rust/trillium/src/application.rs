@@ -1,11 +1,13 @@
-use crate::db::Db;
+use crate::db::{Db, DbConfig};
 use crate::routes::router;
 
-pub fn application() -> impl trillium::Handler {
+pub fn application(db_config: DbConfig) -> impl trillium::Handler {
+    let db = Db::new(db_config);
+    let router = router(db.shared_connection());
     (
         #[cfg(debug_assertions)]
         trillium_logger::logger(),
-        Db::default(),
-        router(),
+        db,
+        router,
     )
 }
@@ -1,36 +1,180 @@
-use sea_orm::{ConnectOptions, Database, DatabaseConnection};
-use std::env;
+use sea_orm::{ConnectOptions, Database, DatabaseConnection, DbErr};
+use std::{
+    env, fmt,
+    str::FromStr,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
 use trillium::{async_trait, Conn, Handler, Info};
 
-#[derive(Debug, Default)]
-pub struct Db(Option<DatabaseConnection>);
+/// The pool, set once the `Db` handler has connected.
+pub type SharedConnection = Arc<OnceLock<DatabaseConnection>>;
+
+#[derive(Debug)]
+pub struct Db {
+    config: DbConfig,
+    conn: SharedConnection,
+}
 
 pub mod cached_world;
 pub mod fortune;
 pub mod world;
 
+/// Connection pool settings, read from the environment so that the async-std, smol
+/// and tokio binaries are configured the same way.
+///
+/// Unset pool sizes and timeouts keep sea-orm's defaults.
+#[derive(Debug, Clone)]
+pub struct DbConfig {
+    url: String,
+    max_connections: Option<u32>,
+    min_connections: Option<u32>,
+    connect_timeout: Option<Duration>,
+    acquire_timeout: Option<Duration>,
+    log_statements: bool,
+    connect_attempts: u32,
+    retry_delay: Duration,
+}
+
+#[derive(Debug)]
+pub enum DbConfigError {
+    Missing(&'static str),
+    Invalid { var: &'static str, value: String },
+}
+
+impl fmt::Display for DbConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing(var) => write!(f, "env var {var} not found"),
+            Self::Invalid { var, value } => write!(f, "invalid value for {var}: {value:?}"),
+        }
+    }
+}
+
+fn var<T: FromStr>(name: &'static str) -> Result<Option<T>, DbConfigError> {
+    match env::var(name) {
+        Ok(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| DbConfigError::Invalid { var: name, value }),
+        Err(_) => Ok(None),
+    }
+}
+
+fn millis_var(name: &'static str) -> Result<Option<Duration>, DbConfigError> {
+    Ok(var(name)?.map(Duration::from_millis))
+}
+
+impl DbConfig {
+    const DEFAULT_CONNECT_ATTEMPTS: u32 = 5;
+    const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+    /// Reads `DATABASE_URL` along with these optional settings:
+    ///
+    /// * `DATABASE_MAX_CONNECTIONS`, `DATABASE_MIN_CONNECTIONS`: pool size bounds
+    /// * `DATABASE_CONNECT_TIMEOUT_MS`, `DATABASE_ACQUIRE_TIMEOUT_MS`: pool timeouts
+    /// * `DATABASE_LOG_STATEMENTS`: `true` to log every statement (default `false`)
+    /// * `DATABASE_CONNECT_ATTEMPTS`: connection attempts at startup (default 5)
+    /// * `DATABASE_RETRY_DELAY_MS`: delay between those attempts (default 500)
+    pub fn from_env() -> Result<Self, DbConfigError> {
+        let config = Self {
+            url: env::var("DATABASE_URL").map_err(|_| DbConfigError::Missing("DATABASE_URL"))?,
+            max_connections: var("DATABASE_MAX_CONNECTIONS")?,
+            min_connections: var("DATABASE_MIN_CONNECTIONS")?,
+            connect_timeout: millis_var("DATABASE_CONNECT_TIMEOUT_MS")?,
+            acquire_timeout: millis_var("DATABASE_ACQUIRE_TIMEOUT_MS")?,
+            log_statements: var("DATABASE_LOG_STATEMENTS")?.unwrap_or(false),
+            connect_attempts: var("DATABASE_CONNECT_ATTEMPTS")?
+                .unwrap_or(Self::DEFAULT_CONNECT_ATTEMPTS)
+                .max(1),
+            retry_delay: millis_var("DATABASE_RETRY_DELAY_MS")?
+                .unwrap_or(Self::DEFAULT_RETRY_DELAY),
+        };
+
+        if let (Some(min), Some(max)) = (config.min_connections, config.max_connections) {
+            if min > max {
+                return Err(DbConfigError::Invalid {
+                    var: "DATABASE_MIN_CONNECTIONS",
+                    value: format!("{min} (above DATABASE_MAX_CONNECTIONS {max})"),
+                });
+            }
+        }
+
+        Ok(config)
+    }
+
+    fn connect_options(&self) -> ConnectOptions {
+        let mut options = ConnectOptions::new(self.url.clone());
+        if let Some(max) = self.max_connections {
+            options.max_connections(max);
+        }
+        if let Some(min) = self.min_connections {
+            options.min_connections(min);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            options.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.acquire_timeout {
+            options.acquire_timeout(timeout);
+        }
+        options.sqlx_logging(self.log_statements);
+        options
+    }
+}
+
 impl Db {
-    pub(crate) async fn connection() -> DatabaseConnection {
-        let db_url = env::var("DATABASE_URL").expect("env var DATABASE_URL not found");
+    pub fn new(config: DbConfig) -> Self {
+        Self {
+            config,
+            conn: SharedConnection::default(),
+        }
+    }
 
-        let connect_options = ConnectOptions::new(db_url.clone());
+    /// The connection this handler opens when it is initialized, for the handlers
+    /// that need it in their own `init`.
+    pub fn shared_connection(&self) -> SharedConnection {
+        self.conn.clone()
+    }
 
-        Database::connect(connect_options)
-            .await
-            .map_err(|e| format!("could not connect to {}: {}", &db_url, e))
-            .unwrap()
+    /// Connects to the database, retrying up to `DATABASE_CONNECT_ATTEMPTS` times so
+    /// the server can start before the database accepts connections.
+    pub(crate) async fn connection(config: &DbConfig) -> Result<DatabaseConnection, DbErr> {
+        let mut attempt = 1;
+        loop {
+            match Database::connect(config.connect_options()).await {
+                Ok(conn) => return Ok(conn),
+                Err(e) if attempt < config.connect_attempts => {
+                    eprintln!(
+                        "could not connect to the database (attempt {attempt}/{}), retrying in {:?}: {e}",
+                        config.connect_attempts, config.retry_delay
+                    );
+                    // async-io drives its own timers, so this works on every runtime.
+                    async_io::Timer::after(config.retry_delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
     }
 }
 
 #[async_trait]
 impl Handler for Db {
     async fn run(&self, conn: Conn) -> Conn {
-        conn.with_state(self.0.as_ref().unwrap().clone())
+        conn.with_state(self.conn.get().unwrap().clone())
     }
 
     async fn init(&mut self, _info: &mut Info) {
-        if self.0.is_none() {
-            self.0 = Some(Self::connection().await);
+        if self.conn.get().is_none() {
+            match Self::connection(&self.config).await {
+                Ok(conn) => {
+                    let _ = self.conn.set(conn);
+                }
+                Err(e) => {
+                    eprintln!("could not connect to the database: {e}");
+                    std::process::exit(1);
+                }
+            }
         }
     }
 }
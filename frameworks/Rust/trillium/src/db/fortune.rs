@@ -1,6 +1,5 @@
 use sea_orm::entity::prelude::*;
 use serde::Serialize;
-use std::cmp::Ordering;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Eq)]
 #[sea_orm(table_name = "Fortune")]
@@ -21,15 +20,3 @@ impl RelationTrait for Relation {
 }
 
 impl ActiveModelBehavior for ActiveModel {}
-
-impl PartialOrd for Model {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for Model {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.message.cmp(&other.message)
-    }
-}
@@ -6,6 +6,7 @@ mod application;
 mod db;
 mod routes;
 use application::application;
+use db::DbConfig;
 use trillium::HttpConfig;
 
 #[cfg(all(feature = "smol", not(feature = "tokio"), not(feature = "async-std")))]
@@ -24,6 +25,11 @@ fn main() {
     #[cfg(debug_assertions)]
     env_logger::init();
 
+    let db_config = DbConfig::from_env().unwrap_or_else(|e| {
+        eprintln!("invalid database configuration: {e}");
+        std::process::exit(1);
+    });
+
     let http_config = HttpConfig::default()
         .with_response_buffer_len(256)
         .with_request_buffer_initial_len(256)
@@ -32,5 +38,5 @@ fn main() {
     config()
         .with_nodelay()
         .with_http_config(http_config)
-        .run(application())
+        .run(application(db_config))
 }
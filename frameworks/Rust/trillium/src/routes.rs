@@ -1,4 +1,9 @@
-use trillium_router::Router;
+use std::fmt::Display;
+use tfb_common::query;
+use trillium::{Conn, Status};
+use trillium_router::{Router, RouterConnExt};
+
+use crate::db::SharedConnection;
 mod cached_queries;
 mod db;
 mod fortune;
@@ -6,7 +11,8 @@ mod json;
 mod plaintext;
 mod queries;
 mod updates;
-pub fn router() -> Router {
+pub fn router(db: SharedConnection) -> Router {
+    let cached_queries = cached_queries::handler(db);
     Router::build(|mut router| {
         router.get("/fortunes", fortune::handler);
         router.get("/json", json::handler);
@@ -16,8 +22,29 @@ pub fn router() -> Router {
         router.get("/plaintext", plaintext::handler);
         router.get("/updates/:updates", updates::handler);
         router.get("/updates", updates::handler);
-        let cached_queries = cached_queries::handler();
-        router.get("/cached-queries/:count", cached_queries.clone());
-        router.get("/cached-queries", cached_queries);
+        #[cfg(debug_assertions)]
+        router.get("/cache-stats", cached_queries.stats_handler());
+        router.get("/cached-queries/:q", cached_queries.clone());
+        router.get("/cached-queries", cached_queries.clone());
     })
 }
+
+/// The `q` query string parameter, or else the `route_param` path segment, clamped to
+/// `1..=500`. A missing or malformed value counts as 1.
+pub(crate) fn queries_param(conn: &Conn, route_param: &str) -> usize {
+    let querystring = conn.querystring();
+    if query::find(querystring.as_bytes()).is_some() {
+        query::queries(querystring)
+    } else {
+        query::clamp(conn.param(route_param))
+    }
+}
+
+/// Respond with a 500. Debug builds also send the cause, to make failures visible
+/// while developing.
+pub(crate) fn internal_error(conn: Conn, _cause: impl Display) -> Conn {
+    let conn = conn.with_status(Status::InternalServerError);
+    #[cfg(debug_assertions)]
+    let conn = conn.with_body(_cause.to_string());
+    conn
+}
@@ -1,80 +1,200 @@
+use super::{internal_error, queries_param};
 use crate::db::{
     cached_world::{Entity as CachedWorlds, Model as CachedWorld},
-    Db, DbConnExt,
+    SharedConnection,
 };
-use futures_lite::StreamExt;
-use moka::future::Cache;
 use sea_orm::{DatabaseConnection, DbErr, EntityTrait};
-use std::{iter, sync::Arc};
-use trillium::{Conn, Handler, Info, Status};
+#[cfg(debug_assertions)]
+use serde::Serialize;
+#[cfg(debug_assertions)]
+use std::sync::atomic::AtomicU64;
+use std::{
+    env,
+    sync::{
+        atomic::{AtomicBool, AtomicI32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use trillium::{Conn, Handler, Info};
 use trillium_api::ApiConnExt;
-use trillium_router::RouterConnExt;
-use unicycle::FuturesUnordered;
 
-pub fn handler() -> CachedWorldHandler {
+#[cfg(all(feature = "smol", not(feature = "tokio"), not(feature = "async-std")))]
+use trillium_smol::spawn;
+
+#[cfg(all(feature = "tokio", not(feature = "smol"), not(feature = "async-std")))]
+use trillium_tokio::spawn;
+
+#[cfg(all(feature = "async-std", not(feature = "smol"), not(feature = "tokio")))]
+use trillium_async_std::spawn;
+
+/// `randomNumber` of a world that is not cached. Real numbers are in `1..=10_000`.
+const MISSING: i32 = 0;
+
+pub fn handler(db: SharedConnection) -> CachedWorldHandler {
+    let refresh = env::var("CACHE_REFRESH_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|&secs| secs > 0)
+        .map(Duration::from_secs);
+
     CachedWorldHandler {
-        cache: Cache::new(10_000),
+        cache: Arc::new(WorldCache {
+            numbers: (0..10_000).map(|_| AtomicI32::new(MISSING)).collect(),
+            #[cfg(debug_assertions)]
+            hits: AtomicU64::new(0),
+            #[cfg(debug_assertions)]
+            misses: AtomicU64::new(0),
+            initialized: AtomicBool::new(false),
+        }),
+        db,
+        refresh,
     }
 }
 
+/// Serves the cached queries test from every world, loaded at startup through the
+/// `Db` handler's connection.
+///
+/// With `CACHE_REFRESH_SECS` set, a background task reloads the worlds at that
+/// interval, so the cache follows the updates test without slowing down a request.
+/// A world missing from the cache fails the request and is counted as a miss.
 #[derive(Debug, Clone)]
 pub struct CachedWorldHandler {
-    cache: Cache<i32, CachedWorld>,
+    cache: Arc<WorldCache>,
+    /// Filled by the `Db` handler, which is initialized first.
+    db: SharedConnection,
+    refresh: Option<Duration>,
 }
 
-impl CachedWorldHandler {
+#[derive(Debug)]
+struct WorldCache {
+    /// Indexed by `id - 1`.
+    numbers: Box<[AtomicI32]>,
+    /// Debug builds only, to keep the counters off the benchmarked path.
+    #[cfg(debug_assertions)]
+    hits: AtomicU64,
+    #[cfg(debug_assertions)]
+    misses: AtomicU64,
+    /// Set by the first of the routes sharing this cache to be initialized.
+    initialized: AtomicBool,
+}
+
+#[cfg(debug_assertions)]
+#[derive(Serialize)]
+struct CacheStats {
+    hits: u64,
+    misses: u64,
+}
+
+impl WorldCache {
     #[inline(always)]
-    fn count_param(conn: &Conn) -> usize {
-        conn.param("count")
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(1)
-            .min(500)
-            .max(1)
+    fn slot(&self, id: i32) -> Option<&AtomicI32> {
+        self.numbers.get(usize::try_from(id).ok()?.checked_sub(1)?)
     }
 
-    #[inline(always)]
-    async fn fetch_cached(
-        &self,
-        db: &DatabaseConnection,
-        id: i32,
-    ) -> Result<CachedWorld, Arc<DbErr>> {
-        self.cache
-            .try_get_with(id, async {
-                CachedWorlds::find_by_id(id)
-                    .one(db)
-                    .await?
-                    .ok_or_else(|| DbErr::RecordNotFound(String::from("not found")))
-            })
-            .await
+    fn store(&self, world: &CachedWorld) {
+        if let Some(slot) = self.slot(world.id) {
+            slot.store(world.random_number, Ordering::Relaxed);
+        }
+    }
+
+    /// Read every world in one query, and return how many were stored.
+    async fn load(&self, db: &DatabaseConnection) -> Result<usize, DbErr> {
+        let worlds = CachedWorlds::find().all(db).await?;
+        for world in &worlds {
+            self.store(world);
+        }
+        Ok(worlds.len())
+    }
+
+    fn get(&self, id: i32) -> Result<CachedWorld, DbErr> {
+        if let Some(number) = self.slot(id).map(|slot| slot.load(Ordering::Relaxed)) {
+            if number != MISSING {
+                #[cfg(debug_assertions)]
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(CachedWorld {
+                    id,
+                    random_number: number,
+                });
+            }
+        }
+
+        #[cfg(debug_assertions)]
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        Err(DbErr::RecordNotFound(format!(
+            "world {id} is not in the cache"
+        )))
+    }
+
+    #[cfg(debug_assertions)]
+    fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl CachedWorldHandler {
+    /// A handler responding with the cache's hit and miss counters, in debug builds.
+    #[cfg(debug_assertions)]
+    pub fn stats_handler(&self) -> impl Handler {
+        let cache = self.cache.clone();
+        move |conn: Conn| {
+            let stats = cache.stats();
+            async move { conn.with_json(&stats) }
+        }
+    }
+
+    fn fetch(&self, count: usize) -> Result<Vec<CachedWorld>, DbErr> {
+        (0..count)
+            .map(|_| self.cache.get(fastrand::i32(1..=10_000)))
+            .collect()
     }
 }
 
 #[trillium::async_trait]
 impl Handler for CachedWorldHandler {
     async fn init(&mut self, _info: &mut Info) {
-        if self.cache.entry_count() == 0 {
-            let db = Db::connection().await;
-            let mut stream = CachedWorlds::find().stream(&db).await.unwrap();
-            while let Some(Ok(world)) = stream.next().await {
-                self.cache.insert(world.id, world).await
+        if self.cache.initialized.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let Some(db) = self.db.get() else {
+            eprintln!("the Db handler must run before the cached queries handler");
+            std::process::exit(1);
+        };
+        match self.cache.load(db).await {
+            Ok(10_000) => {}
+            Ok(loaded) => {
+                eprintln!("only {loaded} of 10000 worlds found");
+                std::process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("could not load the world cache: {e}");
+                std::process::exit(1);
             }
-            self.cache.run_pending_tasks().await;
+        }
+
+        if let Some(every) = self.refresh {
+            let cache = self.cache.clone();
+            let db = db.clone();
+            spawn(async move {
+                loop {
+                    // async-io drives its own timers, so this works on every runtime.
+                    async_io::Timer::after(every).await;
+                    if let Err(e) = cache.load(&db).await {
+                        eprintln!("could not refresh the world cache: {e}");
+                    }
+                }
+            });
         }
     }
 
     async fn run(&self, conn: Conn) -> Conn {
-        let count = Self::count_param(&conn);
-        let db = conn.db();
-        let worlds: Result<Vec<_>, _> =
-            iter::repeat_with(|| self.fetch_cached(db, fastrand::i32(1..=10_000)))
-                .take(count)
-                .collect::<FuturesUnordered<_>>()
-                .try_collect()
-                .await;
-
-        match worlds {
+        let count = queries_param(&conn, "q");
+        match self.fetch(count) {
             Ok(worlds) => conn.with_json(&worlds),
-            Err(_) => conn.with_status(Status::InternalServerError),
+            Err(e) => internal_error(conn, e),
         }
     }
 }
@@ -4,32 +4,35 @@ use crate::db::{
 };
 use futures_lite::StreamExt;
 use sea_orm::EntityTrait;
-use std::collections::BTreeSet;
+use tfb_common::fortune;
 use trillium::{Conn, KnownHeaderName::ContentType, Status};
 use trillium_askama::{AskamaConnExt, Template};
 
 #[derive(Template)]
 #[template(path = "fortunes.html")]
 struct FortuneTemplate<'a> {
-    fortunes: &'a BTreeSet<Fortune>,
+    fortunes: &'a [Fortune],
 }
 
 pub async fn handler(conn: Conn) -> Conn {
     let db = conn.db().clone();
 
-    let mut fortunes = BTreeSet::new();
-    fortunes.insert(Fortune {
-        id: 0,
-        message: String::from("Additional fortune added at request time."),
-    });
-
     let Ok(mut stream) = Fortunes::find().stream(&db).await else {
         return conn.with_status(Status::InternalServerError);
     };
 
+    let mut fortunes = Vec::with_capacity(13);
     while let Some(Ok(fortune)) = stream.next().await {
-        fortunes.insert(fortune);
+        fortunes.push(fortune);
     }
+    fortune::prepare_with(
+        &mut fortunes,
+        |id, message| Fortune {
+            id,
+            message: message.to_string(),
+        },
+        |fortune| &fortune.message,
+    );
 
     conn.render(FortuneTemplate {
         fortunes: &fortunes,
@@ -1,19 +1,14 @@
+use super::queries_param;
 use crate::db::{world::Entity as Worlds, DbConnExt};
 use futures_lite::StreamExt;
 use sea_orm::{entity::prelude::*, TransactionTrait};
 use std::iter;
 use trillium::{Conn, Status};
 use trillium_api::ApiConnExt;
-use trillium_router::RouterConnExt;
 use unicycle::FuturesUnordered;
 
 pub async fn handler(conn: Conn) -> Conn {
-    let queries = conn
-        .param("queries")
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(1)
-        .min(500)
-        .max(1);
+    let queries = queries_param(&conn, "queries");
 
     let Ok(tx) = conn.db().begin().await else {
         return conn.with_status(Status::InternalServerError);
@@ -1,37 +1,55 @@
-use crate::db::{world::Entity as Worlds, DbConnExt};
+use super::{internal_error, queries_param};
+use crate::db::{
+    world::{Entity as Worlds, Model as World},
+    DbConnExt,
+};
 use futures_lite::StreamExt;
-use sea_orm::{entity::prelude::*, IntoActiveModel, Set};
+use sea_orm::{entity::prelude::*, ConnectionTrait, DbBackend, Statement};
 use std::iter;
-use trillium::{Conn, Status};
+use tfb_common::update::dedup_updates_with;
+use trillium::Conn;
 use trillium_api::ApiConnExt;
-use trillium_router::RouterConnExt;
 use unicycle::FuturesUnordered;
 
+/// `tfb_common::update::UPDATE_WORLDS` for the quoted table that the `World`
+/// entity maps to.
+const UPDATE_WORLDS: &str = r#"UPDATE "World" SET randomnumber = v.randomnumber
+    FROM UNNEST($1::int[], $2::int[]) AS v(id, randomnumber)
+    WHERE "World".id = v.id"#;
+
 pub async fn handler(conn: Conn) -> Conn {
-    let queries = conn
-        .param("updates")
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(1)
-        .min(500)
-        .max(1);
+    let queries = queries_param(&conn, "updates");
 
-    let db = conn.db();
-    let worlds = iter::repeat_with(|| async {
-        let mut world = Worlds::find_by_id(fastrand::i32(1..=10_000))
+    match update_worlds(conn.db(), queries).await {
+        Ok(worlds) => conn.with_json(&worlds),
+        Err(e) => internal_error(conn, e),
+    }
+}
+
+async fn update_worlds(db: &DatabaseConnection, queries: usize) -> Result<Vec<World>, DbErr> {
+    let mut worlds = iter::repeat_with(|| async {
+        Worlds::find_by_id(fastrand::i32(1..=10_000))
             .one(db)
             .await?
-            .ok_or_else(|| DbErr::RecordNotFound(String::from("not found")))?
-            .into_active_model();
-        world.random_number = Set(fastrand::i32(1..=10_000));
-        world.update(db).await
+            .ok_or_else(|| DbErr::RecordNotFound(String::from("not found")))
     })
     .take(queries)
     .collect::<FuturesUnordered<_>>()
     .try_collect::<_, _, Vec<_>>()
-    .await;
+    .await?;
 
-    match worlds {
-        Ok(worlds) => conn.with_json(&worlds),
-        Err(_) => conn.with_status(Status::InternalServerError),
+    for world in &mut worlds {
+        world.random_number = fastrand::i32(1..=10_000);
     }
+    // A world drawn twice gets one new number, in the response as in the table.
+    let (ids, numbers) =
+        dedup_updates_with(&mut worlds, |world| (world.id, &mut world.random_number));
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        UPDATE_WORLDS,
+        [ids.into(), numbers.into()],
+    ))
+    .await?;
+
+    Ok(worlds)
 }
@@ -0,0 +1,102 @@
+#![cfg(any(feature = "db",feature = "all",feature = "cache"))]
+use std::cell::RefCell;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use tokio_postgres::types::private::BytesMut;
+
+/// Idle buffers kept per connection; more are dropped when returned.
+const MAX_IDLE: usize = 64;
+
+/// Response bodies of one connection.
+///
+/// A connection serves many requests at once, so every request checks out a
+/// buffer of its own and hands it back when the response has been sent. Once the
+/// pool has grown to the peak number of in-flight requests, serving a request no
+/// longer allocates.
+pub struct BufferPool {
+    idle: RefCell<Vec<BytesMut>>,
+    capacity: usize,
+}
+
+impl BufferPool {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            idle: RefCell::new(Vec::with_capacity(MAX_IDLE)),
+            capacity,
+        }
+    }
+
+    /// An empty buffer, owned by the caller until the guard is dropped.
+    #[inline(always)]
+    pub fn checkout(&self) -> PooledBuf<'_> {
+        let buf = self
+            .idle
+            .borrow_mut()
+            .pop()
+            .unwrap_or_else(|| BytesMut::with_capacity(self.capacity));
+        PooledBuf { pool: self, buf }
+    }
+
+    #[inline(always)]
+    fn give_back(&self, mut buf: BytesMut) {
+        let mut idle = self.idle.borrow_mut();
+        if idle.len() < MAX_IDLE {
+            buf.clear();
+            idle.push(buf);
+        }
+    }
+}
+
+/// A buffer checked out of a [`BufferPool`], returned to it on drop.
+pub struct PooledBuf<'a> {
+    pool: &'a BufferPool,
+    buf: BytesMut,
+}
+
+impl Deref for PooledBuf<'_> {
+    type Target = BytesMut;
+
+    #[inline(always)]
+    fn deref(&self) -> &BytesMut {
+        &self.buf
+    }
+}
+
+impl DerefMut for PooledBuf<'_> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut BytesMut {
+        &mut self.buf
+    }
+}
+
+impl Drop for PooledBuf<'_> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.pool.give_back(mem::take(&mut self.buf));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn returned_buffers_are_cleared_and_reused() {
+        let pool = BufferPool::new(64);
+        let held: Vec<_> = (0..MAX_IDLE + 8)
+            .map(|i| {
+                let mut buf = pool.checkout();
+                buf.extend_from_slice(&i.to_le_bytes());
+                buf
+            })
+            .collect();
+        drop(held);
+
+        // Buffers beyond the idle limit are dropped.
+        assert_eq!(pool.idle.borrow().len(), MAX_IDLE);
+        let buf = pool.checkout();
+        assert!(buf.is_empty());
+        assert!(buf.capacity() >= 64);
+        assert_eq!(pool.idle.borrow().len(), MAX_IDLE - 1);
+    }
+}
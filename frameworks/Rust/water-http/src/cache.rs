@@ -0,0 +1,96 @@
+#![cfg(any(feature = "all", feature = "cache"))]
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::time::Duration;
+use tfb_common::random::WORLD_ROWS;
+use tokio_postgres::{connect, Client, NoTls};
+
+const SELECT_ALL_WORLDS: &str = "SELECT id,randomnumber FROM world";
+
+/// Marks an id whose row has not been loaded. Real random numbers are in `1..=10000`.
+const MISSING: i32 = 0;
+
+static CACHE: OnceLock<WorldCache> = OnceLock::new();
+
+/// `randomNumber` of every world, indexed by `id - 1`.
+///
+/// The table never changes size after `init`, so workers read it without locking;
+/// the optional refresh task and the database fallback only store single numbers.
+pub struct WorldCache {
+    numbers: Box<[AtomicI32]>,
+}
+
+impl WorldCache {
+    fn new() -> Self {
+        Self {
+            numbers: (0..WORLD_ROWS).map(|_| AtomicI32::new(MISSING)).collect(),
+        }
+    }
+
+    /// The cached number of world `id`, or `None` if it is not loaded.
+    #[inline(always)]
+    pub fn get(&self, id: i32) -> Option<i32> {
+        let slot = self.numbers.get(usize::try_from(id).ok()?.checked_sub(1)?)?;
+        match slot.load(Ordering::Relaxed) {
+            MISSING => None,
+            number => Some(number),
+        }
+    }
+
+    /// Cache the number of world `id`. Ids outside the table are ignored.
+    #[inline(always)]
+    pub fn store(&self, id: i32, number: i32) {
+        let slot = usize::try_from(id)
+            .ok()
+            .and_then(|id| id.checked_sub(1))
+            .and_then(|index| self.numbers.get(index));
+        if let Some(slot) = slot {
+            slot.store(number, Ordering::Relaxed);
+        }
+    }
+
+    /// Load every world from `client` and return how many rows were read.
+    pub async fn load(&self, client: &Client) -> Result<usize, tokio_postgres::Error> {
+        let rows = client.query(SELECT_ALL_WORLDS, &[]).await?;
+        for row in &rows {
+            self.store(row.get(0), row.get(1));
+        }
+        Ok(rows.len())
+    }
+}
+
+/// The process-wide cache, created empty on first use.
+#[inline(always)]
+pub fn cache() -> &'static WorldCache {
+    CACHE.get_or_init(WorldCache::new)
+}
+
+/// Reload the cache from `url` every `every` on a dedicated thread, so that the
+/// cached numbers follow the updates made by other clients.
+pub fn spawn_refresh(url: &'static str, every: Duration) {
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        rt.block_on(async move {
+            let (client, connection) = match connect(url, NoTls).await {
+                Ok(pair) => pair,
+                Err(err) => {
+                    eprintln!("cache refresh disabled, cannot connect: {err}");
+                    return;
+                }
+            };
+            tokio::spawn(async move {
+                let _ = connection.await;
+            });
+
+            let mut interval = tokio::time::interval(every);
+            // The first tick completes immediately and the cache was just loaded.
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if let Err(err) = cache().load(&client).await {
+                    eprintln!("cache refresh failed: {err}");
+                }
+            }
+        });
+    });
+}
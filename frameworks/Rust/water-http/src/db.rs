@@ -1,19 +1,19 @@
 #![cfg(any(feature = "db",feature = "all"))]
-use std::{borrow::Cow, io, ptr};
+use std::{borrow::Cow, io};
 use std::fmt::Arguments;
 use std::io::Write;
 use std::mem::MaybeUninit;
 use std::rc::Rc;
-use std::cell::UnsafeCell;
-use std::collections::HashMap;
-use bytes::Buf;
-use nanorand::{Rng, WyRand};
-use tokio_postgres::{connect, Client, Statement, NoTls, Error};
+use std::cell::{RefCell, UnsafeCell};
+use tfb_common::{fortune, WorldRng};
+use tokio_postgres::{connect, Client, Statement, NoTls};
 use tokio_postgres::types::private::BytesMut;
+use crate::buffers::{BufferPool, PooledBuf};
+#[cfg(feature = "all")]
+use crate::cache::cache;
 use crate::models::{Fortune, FortuneTemplate, World};
 use sonic_rs::prelude::WriteExt;
 use yarte::TemplateBytesTrait;
-pub static  mut CACHED_VALUES:Option<HashMap<i32,i32>> = None;
 
 /// Database connection pool with thread-local RNG
 pub struct DbConnectionPool {
@@ -59,40 +59,22 @@ impl DbConnectionPool {
 }
 
 
-/// Reusable buffer pool per connection
-struct BufferPool {
-    body: BytesMut,
-    fortunes: Vec<Fortune>,
-    fortune_output: Vec<u8>,
-}
-
-impl BufferPool {
-    fn new() -> Self {
-        Self {
-            body: BytesMut::with_capacity(4096),
-
-            fortunes: Vec::with_capacity(501),
-            fortune_output: Vec::with_capacity(4096),
-        }
-    }
-
-}
-
 /// PostgreSQL connection wrapper with pre-allocated buffers
 pub struct PgConnection {
     pub cl: Client,
     pub fortune: Statement,
     pub world: Statement,
     pub updates: Vec<Statement>,
-    rang:WyRand,
-    buffers: UnsafeCell<BufferPool>,
+    rng: WorldRng,
+    buffers: BufferPool,
+    /// Scratch list for sorting fortunes, only borrowed between two awaits.
+    fortunes: RefCell<Vec<Fortune>>,
     _connection_task: tokio::task::JoinHandle<()>,
 }
 
 // Safety: Only used within LocalSet, no cross-thread access
 impl PgConnection {
     /// Connect to the database
-
     pub async fn connect(db_url: &str) -> Result<PgConnection, ()> {
         let (cl, conn) = tokio::time::timeout(
             std::time::Duration::from_secs(5),
@@ -121,11 +103,12 @@ impl PgConnection {
             fortune,
             world,
             updates,
-            buffers: UnsafeCell::new(BufferPool::new()),
+            buffers: BufferPool::new(4096),
+            fortunes: RefCell::new(Vec::with_capacity(501)),
             _connection_task: connection_task,
-            rang: WyRand::new()
+            rng: WorldRng::new()
         })
-    }    /// Connect to the database
+    }
 
     #[inline(always)]
     pub fn generate_update_values_stmt(batch_size: usize) -> String {
@@ -145,162 +128,133 @@ impl PgConnection {
         sql
     }
 
-    /// Get mutable access to buffers (safe because connection pool ensures single access)
-    #[inline(always)]
-    fn buffers(&self) -> &mut BufferPool {
-        unsafe { &mut *self.buffers.get() }
-    }
-
     /// Get a single random world - optimized with buffer reuse
     #[inline]
-    pub async fn get_world(&self) -> &[u8] {
-        let rd = (self.rang.clone().generate::<u32>() % 10_000 ) as i32;
+    pub async fn get_world(&self) -> PooledBuf<'_> {
+        let rd = self.rng.id();
         let row = self.cl.query_one(&self.world, &[&rd]).await.unwrap();
-        let buffers = self.buffers();
-        buffers.body.clear();
-        sonic_rs::to_writer(
-            BytesMuteWriter(&mut buffers.body),
-            &World {
-                id: row.get(0),
-                randomnumber: row.get(1),
-            },
-        ).unwrap();
-        buffers.body.chunk()
+        render_json(&self.buffers, &World {
+            id: row.get(0),
+            randomnumber: row.get(1),
+        })
     }
 
     /// Get multiple random worlds - optimized with buffer reuse
-    pub async fn get_worlds(&self, num: usize) -> &[u8] {
-        let buffers = self.buffers();
+    pub async fn get_worlds(&self, num: usize) -> PooledBuf<'_> {
         let mut worlds = Vec::with_capacity(num);
-        for _ in 0..num {
-            let id = (self.rang.clone().generate::<u32>() % 10_000 ) as i32;
+        for id in self.rng.ids(num) {
             let row = self.cl.query_one(&self.world, &[&id]).await.unwrap();
             worlds.push(World {
                 id: row.get(0),
                 randomnumber: row.get(1),
             });
         }
-        buffers.body.clear();
-        sonic_rs::to_writer(BytesMuteWriter(&mut buffers.body), &worlds).unwrap();
-        buffers.body.chunk()
+        render_json(&self.buffers, &worlds)
     }
-    /// Update worlds in batch - optimized with buffer reuse
-    /// Update worlds in batch - optimized with buffer reuse
+    /// Update worlds in batch using one prepared statement per batch size
+    pub async fn update(&self, num: usize) -> PooledBuf<'_> {
 
-    /// Update worlds in batch - optimized with buffer reuse
-
-    /// Update worlds in batch - optimized with buffer reuse
-
-    /// Update worlds in batch - optimized with RETURNING clause to minimize reads
-    /// Update worlds - fetch and update each row to handle duplicates correctly
-    /// Update worlds in batch using CASE statement
-    pub async fn update(&self, num: usize) -> &[u8] {
-
-        let buffers = self.buffers();
         let mut ids:Vec<i32> = Vec::with_capacity(num);
-        let mut rng = self.rang.clone();
         let mut params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
             Vec::with_capacity(num * 2);
         let mut futures =vec![];
-        for _ in 0..num {
-            let w_id = (rng.generate::<u32>() % 10_000 + 1) as i32;
-            ids.push(w_id);
-        }
+        ids.extend(self.rng.ids(num));
         futures.extend(ids.iter().map(|x| async move {self.cl.query_one(&self.world,&[&x]).await}));
         futures_util::future::join_all(futures).await;
         ids.sort_unstable();
         let mut worlds = Vec::with_capacity(num);
         let mut numbers = Vec::with_capacity(num);
-        for index in 0..num {
-            let s_id = (rng.generate::<u32>() % 10_000 + 1 ) as i32;
+        for &id in &ids {
+            let s_id = self.rng.id();
             worlds.push(World{
-                id:ids[index],
+                id,
                 randomnumber:s_id
             });
             numbers.push(s_id);
         }
-        buffers.body.clear();
-        for index in 0..num {
-            params.push(&ids[index]);
-            params.push(&numbers[index]);
+        for (id, number) in ids.iter().zip(&numbers) {
+            params.push(id);
+            params.push(number);
         }
 
         _=self.cl.execute(&self.updates[num - 1], &params).await.unwrap();
-        sonic_rs::to_writer(BytesMuteWriter(&mut buffers.body), &worlds).unwrap();
-        buffers.body.chunk()
+        render_json(&self.buffers, &worlds)
     }
 
 
     /// Tell fortunes - optimized with buffer reuse
-    pub async fn tell_fortune(&self) -> Result<&[u8], ()> {
+    pub async fn tell_fortune(&self) -> Result<PooledBuf<'_>, ()> {
         let res = self.cl.query(&self.fortune, &[]).await.map_err(|_| ())?;
 
-        let buffers = self.buffers();
-        buffers.fortunes.clear();
-        buffers.fortune_output.clear();
-
-        buffers.fortunes.push(Fortune {
-            id: 0,
-            message: Cow::Borrowed("Additional fortune added at request time."),
-        });
+        let mut fortunes = self.fortunes.borrow_mut();
+        fortunes.clear();
 
         for row in res {
-            buffers.fortunes.push(Fortune {
+            fortunes.push(Fortune {
                 id: row.get(0),
                 message: Cow::Owned(row.get(1)),
             });
         }
 
-        buffers.fortunes.sort_unstable_by(|a, b| a.message.cmp(&b.message));
-
-        let template = FortuneTemplate { items: &buffers.fortunes };
-        template.write_call(&mut buffers.fortune_output);
-
-        // Return reference to buffer - zero-copy!
-        Ok(&buffers.fortune_output)
+        Ok(render_fortunes(&self.buffers, &mut fortunes))
     }
 
 
-    pub fn get_cached_queries(&self,num:usize)->&[u8]{
-        let buffers = self.buffers();
+    /// Random worlds from the cache. A world missing from the cache is read from
+    /// the database and cached, so exactly `num` worlds are returned unless that
+    /// read fails.
+    #[cfg(feature = "all")]
+    pub async fn get_cached_queries(&self,num:usize)->Result<PooledBuf<'_>,tokio_postgres::Error>{
+        let cache = cache();
         let mut worlds = Vec::<World>::with_capacity(num);
-        for _ in 0..num {
-            let rd = (self.rang.clone().generate::<u32>() % 10_000 ) as i32;
-            let v = match self.get_world_id_for_cache(rd){
-                None => {continue}
-                Some(e)=>{e}
+        for rd in self.rng.ids(num) {
+            let v = match cache.get(rd) {
+                Some(v) => v,
+                None => {
+                    let row = self.cl.query_one(&self.world, &[&rd]).await?;
+                    let v = row.get(1);
+                    cache.store(rd, v);
+                    v
+                }
             };
             worlds.push(World{
                 id:rd,
-                randomnumber:*v
+                randomnumber:v
             })
         }
-        buffers.body.clear();
-        sonic_rs::to_writer(BytesMuteWriter(&mut buffers.body), &worlds).unwrap();
-        return &buffers.body[..]
+        Ok(render_json(&self.buffers, &worlds))
     }
+}
 
-    fn get_world_id_for_cache(&self, id: i32) -> Option<&i32> {
-        unsafe {
-            let ptr = ptr::addr_of!(CACHED_VALUES);
+/// Serialize `value` into a buffer of its own, checked out of `buffers`.
+#[inline(always)]
+fn render_json<'a, T: sonic_rs::Serialize + ?Sized>(buffers: &'a BufferPool, value: &T) -> PooledBuf<'a> {
+    let mut body = buffers.checkout();
+    sonic_rs::to_writer(BytesMuteWriter(&mut body), value).unwrap();
+    body
+}
 
-            match &*ptr {
-                Some(map) => map.get(&id),
-                None => None,
-            }
-        }
-    }
+/// Add the request-time fortune, sort, and render the page into a buffer of its
+/// own, checked out of `buffers`.
+#[inline(always)]
+fn render_fortunes<'a>(buffers: &'a BufferPool, fortunes: &mut Vec<Fortune>) -> PooledBuf<'a> {
+    fortune::prepare_with(
+        fortunes,
+        |id, message| Fortune {
+            id,
+            message: Cow::Borrowed(message),
+        },
+        |fortune| &fortune.message,
+    );
+
+    let mut body = buffers.checkout();
+    let template = FortuneTemplate { items: fortunes };
+    template.write_call(&mut *body);
+    body
 }
 
 /// Zero-copy writer for BytesMut
 pub struct BytesMuteWriter<'a>(pub &'a mut BytesMut);
-impl BytesMuteWriter<'_> {
-
-    #[inline(always)]
-    pub fn extend_from_slice(&mut self,data:&[u8]){
-        self.0.extend_from_slice(data);
-    }
-}
 impl Write for BytesMuteWriter<'_> {
     #[inline(always)]
     fn write(&mut self, src: &[u8]) -> Result<usize, io::Error> {
@@ -346,7 +300,81 @@ impl WriteExt for BytesMuteWriter<'_> {
 
     #[inline(always)]
     unsafe fn flush_len(&mut self, additional: usize) -> io::Result<()> {
-        self.0.set_len(self.0.len() + additional);
+        unsafe { self.0.set_len(self.0.len() + additional) };
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::rc::Rc;
+    use tokio::task::{spawn_local, yield_now, LocalSet};
+    use crate::buffers::BufferPool;
+    use crate::models::{Fortune, World};
+    use super::{render_fortunes, render_json};
+
+    /// Requests sharing a connection check out their buffers from the same pool,
+    /// and suspend both while querying and between rendering their body and
+    /// sending it. Every body must still be the one its request rendered once the
+    /// others have rendered theirs.
+    #[tokio::test(flavor = "current_thread")]
+    async fn interleaved_queries_and_fortunes_keep_their_bodies() {
+        let local = LocalSet::new();
+
+        local
+            .run_until(async {
+                let buffers = Rc::new(BufferPool::new(64));
+                let tasks: Vec<_> = (0..100)
+                    .map(|request: i32| {
+                        let buffers = buffers.clone();
+                        spawn_local(async move {
+                            // The other requests run while this one queries.
+                            for _ in 0..request % 5 {
+                                yield_now().await;
+                            }
+                            let body = if request % 2 == 0 {
+                                let worlds: Vec<World> = (1..=20)
+                                    .map(|id| World { id, randomnumber: request })
+                                    .collect();
+                                render_json(&buffers, &worlds)
+                            } else {
+                                let mut fortunes = vec![Fortune {
+                                    id: request,
+                                    message: Cow::Owned(format!("fortune of request {request}")),
+                                }];
+                                render_fortunes(&buffers, &mut fortunes)
+                            };
+                            let rendered = body.to_vec();
+                            // And while this one waits to send its body.
+                            for _ in 0..request % 7 {
+                                yield_now().await;
+                            }
+                            assert_eq!(&body[..], &rendered[..], "request {request}");
+                            rendered
+                        })
+                    })
+                    .collect();
+
+                for (request, task) in (0..).zip(tasks) {
+                    let body = task.await.unwrap();
+                    if request % 2 == 0 {
+                        let worlds: Vec<sonic_rs::Value> = sonic_rs::from_slice(&body).unwrap();
+                        assert_eq!(worlds.len(), 20, "request {request}");
+                        let expected = format!("\"randomnumber\":{request}}}");
+                        assert_eq!(std::str::from_utf8(&body).unwrap().matches(&expected).count(), 20);
+                    } else {
+                        let html = std::str::from_utf8(&body).unwrap();
+                        assert!(html.starts_with("<!DOCTYPE html>"), "request {request}");
+                        assert!(html.ends_with("</table></body></html>"), "request {request}");
+                        assert!(html.contains("Additional fortune added at request time."));
+                        assert!(html.contains(&format!("fortune of request {request}<")));
+                        assert_eq!(html.matches("<tr><td>").count(), 2, "request {request}");
+                    }
+                }
+                // The buffers went back to the pool cleared.
+                assert!(buffers.checkout().is_empty());
+            })
+            .await;
+    }
+}
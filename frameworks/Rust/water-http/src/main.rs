@@ -1,7 +1,11 @@
+mod config;
 mod server;
 pub mod models;
 mod db;
+mod cache;
+mod buffers;
 
 fn main() {
+      config::init();
       server::run_server();
 }
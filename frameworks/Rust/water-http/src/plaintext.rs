@@ -1,6 +1,8 @@
 use water_http::{InitControllersRoot, RunServer, WaterController};
 use water_http::server::ServerConfigurations;
 
+mod config;
+
 InitControllersRoot! {
     name:ROOT,
     holder_type:MainType,
@@ -12,18 +14,20 @@ pub type MainType = u8;
 
 
 fn main() {
+    config::init();
     run_server();
 }
 
 
 pub    fn run_server(){
 
-    let cpu_nums = num_cpus::get();
+    let config = config::get();
+    let workers = config.workers(1);
 
 
-    println!("start listening on port 8080 while workers count {cpu_nums}");
-    let mut conf = ServerConfigurations::bind("0.0.0.0",8080);
-    conf.worker_threads_count = cpu_nums * 1 ;
+    println!("start listening on {} while workers count {workers}", config.bind);
+    let mut conf = ServerConfigurations::bind(&config.bind.ip().to_string(),config.bind.port());
+    conf.worker_threads_count = workers ;
 
     RunServer!(
         conf,
@@ -32,8 +36,6 @@ pub    fn run_server(){
     );
 }
 
-const JSON_RESPONSE:&'static [u8] = br#"{"message":"Hello, World!"}"#;
-const P:&'static [u8] = br#"Hello, World!"#;
 
 
 WaterController! {
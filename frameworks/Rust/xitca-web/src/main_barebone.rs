@@ -115,12 +115,12 @@ async fn handler<'h>(req: Request<'h, db_unrealistic::Client>, res: Response<'h>
             json_response(res, &world)
         }
         p if p.starts_with("/q") => {
-            let num = p["/queries?q=".len()..].parse_query();
+            let num = p.split_once('?').map(|(_, q)| q).parse_query();
             let worlds = req.ctx.queries(num).await.unwrap();
             json_response(res, &worlds)
         }
         p if p.starts_with("/u") => {
-            let num = p["/updates?q=".len()..].parse_query();
+            let num = p.split_once('?').map(|(_, q)| q).parse_query();
             let worlds = req.ctx.updates(num).await.unwrap();
             json_response(res, &worlds)
         }
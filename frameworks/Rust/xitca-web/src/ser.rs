@@ -51,17 +51,15 @@ type FortuneMessage = BytesStr;
 type FortuneMessage = String;
 
 impl Fortune {
-    const RUNTIME: &str = "Additional fortune added at request time.";
-
     #[cfg(feature = "zero-copy")]
-    fn runtime() -> Self {
-        Self::new(0, const { BytesStr::from_static(Self::RUNTIME) })
+    fn from_static(id: i32, message: &'static str) -> Self {
+        Self::new(id, BytesStr::from_static(message))
     }
 
     #[cfg(not(feature = "zero-copy"))]
     #[inline]
-    fn runtime() -> Self {
-        Self::new(0, String::from(Self::RUNTIME))
+    fn from_static(id: i32, message: &'static str) -> Self {
+        Self::new(id, String::from(message))
     }
 
     #[inline]
@@ -77,8 +75,9 @@ pub struct Fortunes {
 impl Fortunes {
     #[inline]
     pub fn new(mut items: Vec<Fortune>) -> Self {
-        items.push(Fortune::runtime());
-        items.sort_by(|a, b| a.message.cmp(&b.message));
+        tfb_common::fortune::prepare_with(&mut items, Fortune::from_static, |fortune| {
+            fortune.message.as_str()
+        });
         Self { items }
     }
 
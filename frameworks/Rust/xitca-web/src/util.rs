@@ -5,21 +5,21 @@ pub use parse::QueryParse;
 
 #[cfg(feature = "pg")]
 mod parse {
+    use tfb_common::query;
+
     pub trait QueryParse {
         fn parse_query(self) -> u16;
     }
 
     impl QueryParse for Option<&str> {
         fn parse_query(self) -> u16 {
-            self.and_then(|q| q.find('q').map(|pos| q.split_at(pos + 2).1.parse_query()))
-                .unwrap_or(1)
+            self.map_or(query::MIN_QUERIES as u16, QueryParse::parse_query)
         }
     }
 
     impl QueryParse for &str {
         fn parse_query(self) -> u16 {
-            use atoi::FromRadix10;
-            u16::from_radix_10(self.as_bytes()).0.clamp(1, 500)
+            query::queries(self) as u16
         }
     }
 }
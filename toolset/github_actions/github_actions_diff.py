@@ -32,6 +32,7 @@
 # up-to-date with the target branch to avoid running unwanted tests.
 
 
+import json
 import subprocess
 import os
 import re
@@ -43,6 +44,33 @@ def fw_found_in_changes(test, changes_output):
         changes_output, re.M)
 
 
+def is_test_dir(dir):
+    return os.path.isfile(os.path.join(dir, "benchmark_config.json"))
+
+
+# A test with a "docker_context" above its directory is built from that
+# context, so it is also affected by changes there outside of any test
+# directory, e.g. to a crate shared by several frameworks.
+def context_found_in_changes(test, changes_output):
+    test_dir = "frameworks/" + test
+    if not is_test_dir(test_dir):
+        return False
+    with open(os.path.join(test_dir, "benchmark_config.json")) as config:
+        tests = json.load(config).get("tests", [])
+    contexts = set(
+        os.path.normpath(os.path.join(test_dir, keys["docker_context"]))
+        for group in tests for keys in group.values()
+        if "docker_context" in keys)
+    for change in changes_output.split('\n'):
+        for context in contexts:
+            if not change.startswith(context + "/"):
+                continue
+            parts = os.path.relpath(change, context).split("/")
+            if len(parts) == 1 or not is_test_dir(os.path.join(context, parts[0])):
+                return True
+    return False
+
+
 # Cleans up diffing and grep output and into an array of strings
 def clean_output(output):
     return os.linesep.join([s for s in output.splitlines() if s])
@@ -94,14 +122,14 @@ run_tests = []
 # Break the test env variable down into test directories
 if os.getenv("TESTLANG"):
     dir = "frameworks/" + os.getenv("TESTLANG") + "/"
-    test_dirs = [os.getenv("TESTLANG") + "/" + x for x in [x for x in os.listdir(dir) if os.path.isdir(dir + x)]]
+    test_dirs = [os.getenv("TESTLANG") + "/" + x for x in [x for x in os.listdir(dir) if is_test_dir(dir + x)]]
 
 elif os.getenv("TESTDIR"):
     test_dirs = os.getenv("TESTDIR").split(' ')
 else:
     def get_frameworks(test_lang):
         dir = "frameworks/" + test_lang + "/"
-        return [test_lang + "/" + x for x in [x for x in os.listdir(dir) if os.path.isdir(dir + x)]]
+        return [test_lang + "/" + x for x in [x for x in os.listdir(dir) if is_test_dir(dir + x)]]
     test_dirs = []
     for frameworks in map(get_frameworks, os.listdir("frameworks")):
         for framework in frameworks:
@@ -161,7 +189,7 @@ if re.search(r'^toolset\/(?!(travis\/|continuous\/|scaffolding\/))|^tfb|^Dockerf
     quit_diffing()
 
 for test in test_dirs:
-    if fw_found_in_changes(test, changes):
+    if fw_found_in_changes(test, changes) or context_found_in_changes(test, changes):
         print("Found changes that affect {!s}".format(test))
         run_tests.append(test)
 
@@ -113,6 +113,14 @@ class DockerHelper:
         test_docker_file = '%s.dockerfile' % test.name
         if hasattr(test, 'dockerfile'):
             test_docker_file = test.dockerfile
+        # A test may build from a parent of its directory (e.g. to share a crate
+        # between frameworks); the dockerfile is then located from that context.
+        build_context = test.directory
+        if hasattr(test, 'docker_context'):
+            build_context = os.path.normpath(
+                os.path.join(test.directory, test.docker_context))
+            test_docker_file = os.path.relpath(
+                os.path.join(test.directory, test_docker_file), build_context)
         test_database = ''
         if hasattr(test, 'database'):
             test_database = test.database
@@ -120,14 +128,14 @@ class DockerHelper:
         if build_log_dir is not os.devnull:
             build_log_file = os.path.join(
                 build_log_dir,
-                "%s.log" % test_docker_file.replace(".dockerfile", "").lower())
+                "%s.log" % os.path.basename(test_docker_file).replace(".dockerfile", "").lower())
 
         try:
             self.__build(
                 base_url=self.benchmarker.config.server_docker_host,
                 build_log_file=build_log_file,
                 log_prefix=log_prefix,
-                path=test.directory,
+                path=build_context,
                 dockerfile=test_docker_file,
                 buildargs=({
                     'BENCHMARK_ENV':